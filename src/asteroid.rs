@@ -0,0 +1,129 @@
+// ============================================================================
+// GENERADOR DE ASTEROIDES Y LUNAS PEQUEÑAS
+// Malla irregular con forma de patata a partir de una icoesfera: elipsoide
+// triaxial, lóbulos y abolladuras de baja frecuencia y un campo de cráteres.
+// La misma semilla produce siempre la misma malla
+// ============================================================================
+
+use crate::vector::Vector3;
+use crate::sphere::{Mesh, PLANET_RADIUS};
+use crate::craters::CraterField;
+use crate::shaders::{NoiseSource, NoiseBasis, seeded_random, seeded_direction};
+
+/// Radios angulares de los cráteres más pequeños y más grandes, y exponente
+/// de su distribución de tamaños
+const MIN_CRATER_RADIUS: f32 = 0.05;
+const MAX_CRATER_RADIUS: f32 = 0.45;
+const CRATER_SLOPE: f32 = 1.8;
+
+/// Parámetros de forma de un cuerpo irregular
+#[derive(Debug, Clone)]
+pub struct AsteroidShape {
+    pub seed: u32,
+    /// Alargamiento del elipsoide base en [0, 1] (0 = esfera)
+    pub elongation: f32,
+    /// Amplitud de los lóbulos y abolladuras grandes en [0, 1]
+    pub lumpiness: f32,
+    /// Número de cráteres del campo
+    pub crater_count: u32,
+    /// Subdivisiones de la icoesfera de partida
+    pub subdivisions: u32,
+}
+
+/// Bulto (o abolladura si `amplitude` < 0) gaussiano centrado en una dirección
+struct Lobe {
+    center: Vector3,
+    width: f32,
+    amplitude: f32,
+}
+
+impl AsteroidShape {
+    pub fn new(seed: u32, elongation: f32, lumpiness: f32, crater_count: u32) -> Self {
+        AsteroidShape {
+            seed,
+            elongation: elongation.clamp(0.0, 1.0),
+            lumpiness: lumpiness.clamp(0.0, 1.0),
+            crater_count,
+            subdivisions: 4,
+        }
+    }
+
+    fn random(&self, index: u32) -> f32 {
+        seeded_random(self.seed, index)
+    }
+
+    /// Semiejes del elipsoide base; su producto es 1 para conservar el volumen
+    fn axes(&self) -> Vector3 {
+        let long = 1.0 + self.elongation * (0.4 + self.random(0) * 0.4);
+        let middle = 1.0 - self.elongation * self.random(1) * 0.25;
+        Vector3::new(long, middle, 1.0 / (long * middle))
+    }
+
+    fn lobes(&self) -> Vec<Lobe> {
+        let count = 3 + (self.random(2) * 3.0) as u32;
+        (0..count)
+            .map(|i| {
+                let base = 100 + i * 5;
+                // Más abolladuras que bultos: los impactos grandes excavan
+                let sign = if self.random(base + 2) < 0.6 { -1.0 } else { 1.0 };
+                Lobe {
+                    center: seeded_direction(self.seed, base),
+                    width: 0.5 + self.random(base + 3) * 0.7,
+                    amplitude: sign * self.lumpiness * (0.08 + self.random(base + 4) * 0.12),
+                }
+            })
+            .collect()
+    }
+
+    /// Cráteres del cuerpo; también sirven a los shaders como máscara de albedo
+    pub fn crater_field(&self) -> CraterField {
+        CraterField::new(
+            self.seed.wrapping_add(1),
+            self.crater_count,
+            MIN_CRATER_RADIUS,
+            MAX_CRATER_RADIUS,
+            CRATER_SLOPE,
+        )
+    }
+
+    /// Dirección sobre la esfera de partida que corresponde a un punto de la
+    /// malla deformada (deshace el estiramiento del elipsoide)
+    pub fn surface_direction(&self, object_position: Vector3) -> Vector3 {
+        let axes = self.axes();
+        Vector3::new(object_position.x / axes.x, object_position.y / axes.y, object_position.z / axes.z).normalize()
+    }
+
+    /// Malla deformada lista para dibujar con cualquier `PlanetShader`
+    pub fn mesh(&self) -> Mesh {
+        let mut mesh = Mesh::icosphere(self.subdivisions);
+        let noise = NoiseSource::new(self.seed);
+        let axes = self.axes();
+        let lobes = self.lobes();
+        let craters = self.crater_field();
+        // Los cráteres menores que un triángulo de la malla no se pueden modelar:
+        // la huella es media arista del icosaedro (1.1 rad) tras las subdivisiones
+        let mesh_footprint = 0.55 / (1 << self.subdivisions) as f32;
+
+        for vertex in &mut mesh.vertices {
+            let direction = vertex.position.normalize();
+
+            // === DEFORMACIONES GRANDES: elipsoide, lóbulos y ondulación suave ===
+            let mut scale = 1.0;
+            for lobe in &lobes {
+                let angle = direction.dot(&lobe.center).clamp(-1.0, 1.0).acos();
+                scale += lobe.amplitude * (-(angle / lobe.width).powi(2)).exp();
+            }
+            scale += noise.fbm3d_basis(NoiseBasis::Simplex, direction.x * 1.5, direction.y * 1.5, direction.z * 1.5, 3)
+                * self.lumpiness * 0.3;
+            let body = Vector3::new(direction.x * axes.x, direction.y * axes.y, direction.z * axes.z)
+                * (PLANET_RADIUS * scale.max(0.3));
+
+            // === CRÁTERES: estampados a lo largo del radio sobre la forma ya deformada ===
+            let crater_offset = craters.height(direction, mesh_footprint);
+            vertex.position = body * (1.0 + crater_offset).max(0.3);
+        }
+
+        mesh.recompute_normals();
+        mesh
+    }
+}
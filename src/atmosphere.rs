@@ -0,0 +1,229 @@
+// ============================================================================
+// ATMÓSFERA CON DISPERSIÓN
+// Capa esférica algo mayor que el planeta. Para cada punto de la capa se
+// integra la dispersión simple de Rayleigh y Mie a lo largo del rayo de vista:
+// limbo azul, terminador anaranjado y halo suave sobre el fondo
+// ============================================================================
+
+use crate::vector::Vector3;
+use crate::sphere::PLANET_RADIUS;
+use crate::shaders::{ShaderColor, ShaderUniforms, PlanetShader, RenderLayer};
+
+/// Muestras a lo largo del rayo de vista y del rayo hacia la luz
+const VIEW_SAMPLES: usize = 10;
+const LIGHT_SAMPLES: usize = 4;
+
+/// Profundidad óptica de Rayleigh en el cenit a 550 nm con densidad 1
+const RAYLEIGH_ZENITH_DEPTH: f32 = 0.1;
+/// Profundidad óptica de Mie en el cenit con densidad 1 (gris, no depende de λ)
+const MIE_ZENITH_DEPTH: f32 = 0.02;
+/// La bruma de Mie se concentra cerca del suelo
+const MIE_HEIGHT_RATIO: f32 = 0.3;
+/// Anisotropía de Mie: cuanto más cerca de 1, más dispersión hacia delante
+const MIE_G: f32 = 0.76;
+/// Intensidad de la luz estelar que entra en la atmósfera
+const SUN_INTENSITY: f32 = 14.0;
+
+/// Atmósfera de un planeta. Se dibuja como capa (`layer`) con su propio shader
+/// sobre la malla del planeta agrandada hasta el borde de la atmósfera
+#[derive(Debug, Clone)]
+pub struct Atmosphere {
+    /// Radio del planeta en unidades del objeto
+    pub planet_radius: f32,
+    /// Radio exterior de la atmósfera en unidades del objeto
+    pub outer_radius: f32,
+    /// Altura de escala de Rayleigh como fracción del espesor de la atmósfera
+    pub scale_height: f32,
+    /// Longitudes de onda (nm) que representan los canales rojo, verde y azul
+    pub wavelengths: [f32; 3],
+    /// Multiplicador de la densidad del gas (1 = parecido a la Tierra)
+    pub density: f32,
+}
+
+impl Atmosphere {
+    /// `thickness` se expresa en radios del planeta (`PLANET_RADIUS`)
+    pub fn new(thickness: f32, scale_height: f32, wavelengths: [f32; 3], density: f32) -> Self {
+        Atmosphere {
+            planet_radius: PLANET_RADIUS,
+            outer_radius: PLANET_RADIUS * (1.0 + thickness.max(0.01)),
+            scale_height: scale_height.max(0.01),
+            wavelengths: wavelengths.map(|w| w.max(100.0)),
+            density: density.max(0.0),
+        }
+    }
+
+    /// Capa de render que cubre el planeta hasta el borde de la atmósfera
+    pub fn layer(&self) -> RenderLayer {
+        RenderLayer {
+            shader: Box::new(self.clone()),
+            radius_scale: self.outer_radius / self.planet_radius,
+            rotation_speed: 0.0,
+        }
+    }
+
+    fn rayleigh_height(&self) -> f32 {
+        (self.outer_radius - self.planet_radius) * self.scale_height
+    }
+
+    fn mie_height(&self) -> f32 {
+        self.rayleigh_height() * MIE_HEIGHT_RATIO
+    }
+
+    /// Coeficientes de extinción de Rayleigh por canal: proporcionales a 1/λ⁴
+    fn rayleigh_coefficients(&self) -> [f32; 3] {
+        let base = self.density * RAYLEIGH_ZENITH_DEPTH / self.rayleigh_height();
+        self.wavelengths.map(|w| base * (550.0 / w).powi(4))
+    }
+
+    fn mie_coefficient(&self) -> f32 {
+        self.density * MIE_ZENITH_DEPTH / self.mie_height()
+    }
+
+    /// Densidades relativas (Rayleigh, Mie) a una distancia `radius` del centro
+    fn densities(&self, radius: f32) -> (f32, f32) {
+        let height = (radius - self.planet_radius).max(0.0);
+        ((-height / self.rayleigh_height()).exp(), (-height / self.mie_height()).exp())
+    }
+
+    /// Luz dispersada hacia la cámara a lo largo del rayo `origin + t * direction`
+    /// (con el planeta en el origen del mundo) y transmitancia del rayo por canal
+    pub fn scatter(&self, origin: Vector3, direction: Vector3, light_direction: Vector3) -> ([f32; 3], [f32; 3]) {
+        let no_effect = ([0.0; 3], [1.0; 3]);
+        let Some((near, far)) = ray_sphere(origin, direction, self.outer_radius) else {
+            return no_effect;
+        };
+        // El rayo termina al chocar con el suelo
+        let far = match ray_sphere(origin, direction, self.planet_radius) {
+            Some((ground, _)) if ground > 0.0 => far.min(ground),
+            _ => far,
+        };
+        let near = near.max(0.0);
+        if far <= near {
+            return no_effect;
+        }
+
+        let rayleigh = self.rayleigh_coefficients();
+        let mie = self.mie_coefficient();
+        let step = (far - near) / VIEW_SAMPLES as f32;
+
+        let mut view_depth = (0.0, 0.0);
+        let mut sum_rayleigh = [0.0; 3];
+        let mut sum_mie = [0.0; 3];
+
+        for i in 0..VIEW_SAMPLES {
+            let point = origin + direction * (near + step * (i as f32 + 0.5));
+            let (density_r, density_m) = self.densities(point.length());
+            view_depth.0 += density_r * step;
+            view_depth.1 += density_m * step;
+
+            // Los puntos a la sombra del planeta no reciben luz
+            let Some(light_depth) = self.light_depth(point, light_direction) else {
+                continue;
+            };
+
+            for c in 0..3 {
+                let optical_depth = rayleigh[c] * (view_depth.0 + light_depth.0)
+                    + mie * 1.1 * (view_depth.1 + light_depth.1);
+                let attenuation = (-optical_depth).exp();
+                sum_rayleigh[c] += attenuation * density_r * step;
+                sum_mie[c] += attenuation * density_m * step;
+            }
+        }
+
+        let mu = direction.dot(&light_direction);
+        let phase_rayleigh = 3.0 / (16.0 * std::f32::consts::PI) * (1.0 + mu * mu);
+        let phase_mie = henyey_greenstein(mu, MIE_G);
+
+        let mut light = [0.0; 3];
+        let mut transmittance = [0.0; 3];
+        for c in 0..3 {
+            light[c] = SUN_INTENSITY * (sum_rayleigh[c] * rayleigh[c] * phase_rayleigh + sum_mie[c] * mie * phase_mie);
+            transmittance[c] = (-(rayleigh[c] * view_depth.0 + mie * 1.1 * view_depth.1)).exp();
+        }
+        (light, transmittance)
+    }
+
+    /// Color de la luz estelar que llega a `world_position` tras atravesar la
+    /// atmósfera: con el sol bajo el azul se pierde y la luz se vuelve naranja
+    pub fn sunlight(&self, world_position: Vector3, light_direction: Vector3) -> ShaderColor {
+        // Se mide desde el suelo ideal para que el relieve no cambie el color
+        let ground = world_position.normalize() * self.planet_radius;
+        let (depth_r, depth_m) = self.light_depth(ground, light_direction).unwrap_or((0.0, 0.0));
+        let rayleigh = self.rayleigh_coefficients();
+        let mie = self.mie_coefficient();
+        let channel = |c: usize| (-(rayleigh[c] * depth_r + mie * 1.1 * depth_m)).exp();
+        ShaderColor::new(channel(0), channel(1), channel(2), 1.0)
+    }
+
+    /// Profundidad óptica (Rayleigh, Mie) desde `point` hasta salir de la
+    /// atmósfera hacia la luz; `None` si el planeta tapa la luz
+    fn light_depth(&self, point: Vector3, light_direction: Vector3) -> Option<(f32, f32)> {
+        if let Some((ground, _)) = ray_sphere(point, light_direction, self.planet_radius) {
+            if ground > 0.0 {
+                return None;
+            }
+        }
+        let (_, exit) = ray_sphere(point, light_direction, self.outer_radius)?;
+        let step = exit.max(0.0) / LIGHT_SAMPLES as f32;
+
+        let mut depth = (0.0, 0.0);
+        for i in 0..LIGHT_SAMPLES {
+            let sample = point + light_direction * (step * (i as f32 + 0.5));
+            let (density_r, density_m) = self.densities(sample.length());
+            depth.0 += density_r * step;
+            depth.1 += density_m * step;
+        }
+        Some(depth)
+    }
+}
+
+impl PlanetShader for Atmosphere {
+    fn vertex_shader(&self, position: Vector3, normal: Vector3, _uv: (f32, f32), _uniforms: &ShaderUniforms) -> (Vector3, Vector3) {
+        (position, normal)
+    }
+
+    fn fragment_shader(&self, position: Vector3, _normal: Vector3, _uv: (f32, f32), uniforms: &ShaderUniforms) -> ShaderColor {
+        let direction = (position - uniforms.camera_position).normalize();
+        let light_dir = uniforms.light_direction.normalize();
+        let (light, transmittance) = self.scatter(uniforms.camera_position, direction, light_dir);
+
+        // Exposición suave para que el limbo no se sature
+        let exposed = light.map(|l| 1.0 - (-l).exp());
+
+        // Mezcla alfa que equivale a `fondo * transmitancia + luz dispersada`
+        let opacity = 1.0 - (transmittance[0] + transmittance[1] + transmittance[2]) / 3.0;
+        let alpha = opacity.max(exposed[0]).max(exposed[1]).max(exposed[2]).clamp(0.0, 1.0);
+        if alpha <= 0.0 {
+            return ShaderColor::new(0.0, 0.0, 0.0, 0.0);
+        }
+
+        ShaderColor::new(
+            (exposed[0] / alpha).clamp(0.0, 1.0),
+            (exposed[1] / alpha).clamp(0.0, 1.0),
+            (exposed[2] / alpha).clamp(0.0, 1.0),
+            alpha,
+        )
+    }
+}
+
+/// Distancias de entrada y salida del rayo en una esfera centrada en el origen
+fn ray_sphere(origin: Vector3, direction: Vector3, radius: f32) -> Option<(f32, f32)> {
+    let b = origin.dot(&direction);
+    let c = origin.dot(&origin) - radius * radius;
+    let discriminant = b * b - c;
+    if discriminant < 0.0 {
+        return None;
+    }
+    let root = discriminant.sqrt();
+    let far = -b + root;
+    if far < 0.0 {
+        return None;
+    }
+    Some((-b - root, far))
+}
+
+/// Función de fase de Henyey-Greenstein normalizada sobre la esfera
+fn henyey_greenstein(mu: f32, g: f32) -> f32 {
+    let g2 = g * g;
+    (1.0 - g2) / (4.0 * std::f32::consts::PI * (1.0 + g2 - 2.0 * g * mu).powf(1.5))
+}
@@ -0,0 +1,155 @@
+// ============================================================================
+// AURORA POLAR
+// Capa emisiva dentro de la atmósfera: cortinas de luz a lo largo de los
+// óvalos aurorales que rodean los polos magnéticos, visibles solo de noche
+// ============================================================================
+
+use crate::vector::Vector3;
+use crate::atmosphere::Atmosphere;
+use crate::shaders::{ShaderColor, ShaderUniforms, PlanetShader, RenderLayer, NoiseSource, NoiseBasis, smoothstep, mix, mix_color, tangent_frame, octave_fade, night_mask};
+
+/// Altura de las cortinas como fracción del espesor de la atmósfera
+const CURTAIN_ALTITUDE: f32 = 0.6;
+
+/// Rayos verticales de la cortina por radián de longitud magnética
+const RAY_FREQUENCY: f32 = 40.0;
+
+pub struct AuroraShader {
+    noise: NoiseSource,
+    /// Eje magnético en espacio de objeto (inclinado respecto al eje de giro)
+    magnetic_axis: Vector3,
+    /// Actividad geomagnética en [0, 1]: brillo, anchura y extensión del óvalo
+    activity: f32,
+}
+
+impl AuroraShader {
+    /// `magnetic_tilt` es el ángulo (radianes) entre el eje magnético y el de giro
+    pub fn new(seed: u32, magnetic_tilt: f32, activity: f32) -> Self {
+        AuroraShader {
+            noise: NoiseSource::new(seed),
+            magnetic_axis: Vector3::new(magnetic_tilt.sin(), magnetic_tilt.cos(), 0.0),
+            activity: activity.clamp(0.0, 1.0),
+        }
+    }
+
+    /// Capa a media altura de la atmósfera. Debe girar con el suelo para que
+    /// los polos magnéticos acompañen al planeta
+    pub fn layer(self, atmosphere: &Atmosphere, rotation_speed: f32) -> RenderLayer {
+        let thickness = atmosphere.outer_radius / atmosphere.planet_radius - 1.0;
+        RenderLayer {
+            shader: Box::new(self),
+            radius_scale: 1.0 + thickness * CURTAIN_ALTITUDE,
+            rotation_speed,
+        }
+    }
+
+    /// Coordenadas magnéticas de una dirección: (colatitud desde el polo más
+    /// cercano, longitud alrededor del eje, hemisferio ±1)
+    fn magnetic_coordinates(&self, direction: Vector3) -> (f32, f32, f32) {
+        let along = direction.dot(&self.magnetic_axis);
+        let (tangent, bitangent) = tangent_frame(self.magnetic_axis);
+        let colatitude = along.abs().min(1.0).acos();
+        let longitude = direction.dot(&bitangent).atan2(direction.dot(&tangent));
+        (colatitude, longitude, along.signum())
+    }
+
+    /// Luz de la cortina en una dirección: color normalizado y en `a` su intensidad
+    fn curtain(&self, direction: Vector3, footprint: f32, time: f32) -> ShaderColor {
+        // === PALETA DE EMISIÓN ===
+        let oxygen_green = ShaderColor::from_rgb(60, 255, 130);    // Oxígeno, 557.7 nm
+        let oxygen_red = ShaderColor::from_rgb(255, 40, 70);       // Oxígeno alto, 630 nm
+
+        let (colatitude, longitude, hemisphere) = self.magnetic_coordinates(direction);
+
+        // El óvalo se ensancha y baja de latitud con la actividad
+        let oval = 0.3 + self.activity * 0.15;
+        let width = 0.04 + self.activity * 0.04;
+        if (colatitude - oval).abs() > width * 6.0 + 0.2 {
+            return ShaderColor::new(0.0, 0.0, 0.0, 0.0);
+        }
+
+        // Coordenadas sobre el óvalo sin costura: un círculo en el plano
+        let (ring_x, ring_y) = (longitude.cos(), longitude.sin());
+        let seed_offset = hemisphere * 10.0;
+
+        // === DEFORMACIÓN DEL DOMINIO: grandes meandros y pliegues de la cortina ===
+        let meander = self.noise.fbm3d_basis(NoiseBasis::Simplex, ring_x * 2.0, ring_y * 2.0, time * 0.08 + seed_offset, 3);
+        let fold = self.noise.fbm3d_basis(
+            NoiseBasis::Simplex,
+            ring_x * 6.0 + meander * 2.0,
+            ring_y * 6.0 - meander * 2.0,
+            time * 0.25 + seed_offset,
+            3,
+        );
+        let distance = colatitude - oval - meander * 0.1 * (0.5 + self.activity) - fold * 0.035;
+
+        // Borde inferior (hacia el ecuador) nítido y borde superior difuso
+        let spread = if distance > 0.0 { width * 0.5 } else { width * 1.6 };
+        let band = (-(distance / spread).powi(2)).exp();
+
+        // === RAYOS: estrías finas a lo largo de la cortina que se desplazan ===
+        let rays = self.noise.fbm3d_basis(
+            NoiseBasis::Simplex,
+            ring_x * RAY_FREQUENCY * 0.5 + fold * 3.0,
+            ring_y * RAY_FREQUENCY * 0.5,
+            time * 0.6 + seed_offset,
+            2,
+        );
+        let rays = (0.5 + rays * 0.8).clamp(0.0, 1.0).powf(1.5);
+        let ray_frequency = RAY_FREQUENCY / oval.sin().max(0.1);
+        let rays = mix(0.35, rays, octave_fade(ray_frequency, footprint));
+
+        // Ondas de brillo que recorren el óvalo
+        let surge = 0.75 + 0.25 * (longitude * 3.0 - time * 0.9 + meander * 4.0).sin();
+
+        // El rojo domina en lo alto de la cortina (lado polar) y con mucha actividad
+        let red = smoothstep(0.0, -width * 2.0, distance) * (0.4 + self.activity * 0.6);
+        let color = mix_color(oxygen_green, oxygen_red, red);
+
+        let intensity = band * (0.3 + rays * 0.9) * surge * (0.35 + self.activity * 0.9);
+        ShaderColor::new(color.r, color.g, color.b, intensity.clamp(0.0, 1.0))
+    }
+
+    /// Cortina vista desde la cámara: solo en el lado nocturno y más intensa
+    /// de canto, donde la línea de vista recorre más luz
+    fn glow(&self, position: Vector3, normal: Vector3, uniforms: &ShaderUniforms) -> ShaderColor {
+        let night = night_mask(normal, uniforms.light_direction.normalize());
+        if night <= 0.0 || self.activity <= 0.0 {
+            return ShaderColor::new(0.0, 0.0, 0.0, 0.0);
+        }
+
+        let object_position = uniforms.object_position(position);
+        let footprint = uniforms.footprint(position) / object_position.length();
+        let curtain = self.curtain(object_position.normalize(), footprint, uniforms.time);
+
+        let view_dir = (uniforms.camera_position - position).normalize();
+        let grazing = 1.0 / normal.dot(&view_dir).abs().max(0.4);
+
+        ShaderColor::new(curtain.r, curtain.g, curtain.b, (curtain.a * night * grazing).clamp(0.0, 1.0))
+    }
+}
+
+impl PlanetShader for AuroraShader {
+    fn vertex_shader(&self, position: Vector3, normal: Vector3, _uv: (f32, f32), _uniforms: &ShaderUniforms) -> (Vector3, Vector3) {
+        (position, normal)
+    }
+
+    fn fragment_shader(&self, position: Vector3, normal: Vector3, _uv: (f32, f32), uniforms: &ShaderUniforms) -> ShaderColor {
+        self.glow(position, normal, uniforms)
+    }
+
+    fn emission(&self, position: Vector3, normal: Vector3, uniforms: &ShaderUniforms) -> ShaderColor {
+        emitted_light(self.glow(position, normal, uniforms))
+    }
+
+    /// La cortina se evalúa una vez y sirve de color translúcido y de emisión
+    fn shade(&self, position: Vector3, normal: Vector3, _uv: (f32, f32), uniforms: &ShaderUniforms) -> (ShaderColor, ShaderColor) {
+        let glow = self.glow(position, normal, uniforms);
+        (glow, emitted_light(glow))
+    }
+}
+
+/// Luz que aporta la cortina: su color pesado por la opacidad
+fn emitted_light(glow: ShaderColor) -> ShaderColor {
+    ShaderColor::new(glow.r * glow.a, glow.g * glow.a, glow.b * glow.a, 1.0)
+}
@@ -0,0 +1,250 @@
+// ============================================================================
+// RELOJ DE SIMULACIÓN
+// Un único tiempo de simulación del que dependen todos los cuerpos y los
+// shaders. Se puede pausar, acelerar o frenar (x0.1 a x1000), invertir,
+// avanzar fotograma a fotograma o saltar a un instante concreto
+// ============================================================================
+
+/// Multiplicadores de velocidad que se recorren con +/-
+const SPEEDS: [f32; 13] = [0.1, 0.25, 0.5, 1.0, 2.0, 5.0, 10.0, 25.0, 50.0, 100.0, 250.0, 500.0, 1000.0];
+
+/// Posición de x1 en `SPEEDS`
+const NORMAL_SPEED: usize = 3;
+
+/// Tiempo real de un paso fotograma a fotograma (a 60 FPS)
+const FRAME_STEP: f32 = 1.0 / 60.0;
+
+/// Periodo con el que se envuelve el tiempo de los shaders: lo bastante largo
+/// para que el salto al envolver sea raro (una vez por hora simulada) y lo
+/// bastante corto para que en `f32` avance suave fotograma a fotograma
+const SHADER_TIME_PERIOD: f64 = 3600.0;
+
+/// Tiempo real máximo por fotograma: tras un tirón (ventana arrastrada, carga)
+/// la simulación no da un salto
+const MAX_FRAME_TIME: f32 = 0.25;
+
+pub struct SimulationClock {
+    /// Segundos de simulación; en `f64` para no perder precisión a x1000
+    time: f64,
+    speed_index: usize,
+    paused: bool,
+    reversed: bool,
+    /// Paso de un fotograma pendiente para el próximo `tick`
+    pending_step: bool,
+    /// Velocidad máxima permitida ahora mismo (ver `limit_speed`)
+    speed_limit: f32,
+}
+
+impl SimulationClock {
+    pub fn new() -> Self {
+        SimulationClock {
+            time: 0.0,
+            speed_index: NORMAL_SPEED,
+            paused: false,
+            reversed: false,
+            pending_step: false,
+            speed_limit: f32::INFINITY,
+        }
+    }
+
+    /// Instante actual de la simulación, en segundos
+    pub fn time(&self) -> f64 {
+        self.time
+    }
+
+    /// Tiempo para `ShaderUniforms::time`: el del reloj envuelto en f64 antes
+    /// de pasar a f32, que en instantes grandes avanzaría a saltos. El resto
+    /// conserva el signo para que al cruzar t = 0 marcha atrás no salte
+    pub fn shader_time(&self) -> f32 {
+        (self.time % SHADER_TIME_PERIOD) as f32
+    }
+
+    /// Segundos de simulación por segundo real, sin signo
+    pub fn speed(&self) -> f32 {
+        SPEEDS[self.speed_index]
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn is_reversed(&self) -> bool {
+        self.reversed
+    }
+
+    pub fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+    }
+
+    /// Invierte el sentido del tiempo
+    pub fn toggle_reverse(&mut self) {
+        self.reversed = !self.reversed;
+    }
+
+    pub fn faster(&mut self) {
+        if self.speed_index + 1 < SPEEDS.len() && SPEEDS[self.speed_index + 1] <= self.speed_limit {
+            self.speed_index += 1;
+        }
+    }
+
+    pub fn slower(&mut self) {
+        self.speed_index = self.speed_index.saturating_sub(1);
+    }
+
+    /// Limita la velocidad a `limit` (como mínimo la más lenta), bajándola si
+    /// hace falta; `None` quita el límite
+    pub fn limit_speed(&mut self, limit: Option<f32>) {
+        self.speed_limit = limit.unwrap_or(f32::INFINITY);
+        while self.speed_index > 0 && SPEEDS[self.speed_index] > self.speed_limit {
+            self.speed_index -= 1;
+        }
+    }
+
+    /// Pausa el reloj y deja pendiente el avance de un solo fotograma (a la
+    /// velocidad y en el sentido actuales)
+    pub fn step_frame(&mut self) {
+        self.paused = true;
+        self.pending_step = true;
+    }
+
+    /// Salta directamente al instante `time`
+    pub fn jump_to(&mut self, time: f64) {
+        self.time = time;
+    }
+
+    /// Avanza el reloj según el tiempo real del fotograma y devuelve los
+    /// segundos de simulación transcurridos: cero en pausa, negativos al revés
+    pub fn tick(&mut self, frame_time: f32) -> f32 {
+        let real_dt = if self.pending_step {
+            self.pending_step = false;
+            FRAME_STEP
+        } else if self.paused {
+            0.0
+        } else {
+            frame_time.min(MAX_FRAME_TIME)
+        };
+
+        let direction = if self.reversed { -1.0 } else { 1.0 };
+        let dt = real_dt * self.speed() * direction;
+        self.time += dt as f64;
+        dt
+    }
+}
+
+impl Default for SimulationClock {
+    fn default() -> Self {
+        SimulationClock::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: f64, b: f64) {
+        assert!((a - b).abs() < 1e-6, "{} != {}", a, b);
+    }
+
+    #[test]
+    fn runs_at_normal_speed_by_default() {
+        let mut clock = SimulationClock::new();
+        assert_eq!(clock.speed(), 1.0);
+        assert_close(clock.tick(0.1) as f64, 0.1);
+        assert_close(clock.time(), 0.1);
+    }
+
+    #[test]
+    fn pause_stops_time() {
+        let mut clock = SimulationClock::new();
+        clock.tick(0.2);
+        clock.toggle_pause();
+        assert_eq!(clock.tick(0.1), 0.0);
+        assert_close(clock.time(), 0.2);
+        clock.toggle_pause();
+        clock.tick(0.1);
+        assert_close(clock.time(), 0.3);
+    }
+
+    #[test]
+    fn reverse_runs_time_backwards() {
+        let mut clock = SimulationClock::new();
+        clock.faster();
+        clock.toggle_reverse();
+        assert_close(clock.tick(0.1) as f64, -0.2);
+        assert_close(clock.time(), -0.2);
+    }
+
+    #[test]
+    fn frame_step_advances_once_and_pauses() {
+        let mut clock = SimulationClock::new();
+        clock.faster();
+        clock.step_frame();
+        assert!(clock.is_paused());
+        assert_close(clock.tick(0.5) as f64, (FRAME_STEP * 2.0) as f64);
+        assert_eq!(clock.tick(0.5), 0.0);
+
+        // En sentido inverso el paso retrocede
+        clock.toggle_reverse();
+        clock.step_frame();
+        clock.tick(0.5);
+        assert_close(clock.time(), 0.0);
+    }
+
+    #[test]
+    fn jump_sets_the_time_directly() {
+        let mut clock = SimulationClock::new();
+        clock.jump_to(12345.5);
+        assert_close(clock.time(), 12345.5);
+        clock.tick(0.125);
+        assert_close(clock.time(), 12345.625);
+    }
+
+    #[test]
+    fn long_frames_are_clamped() {
+        let mut clock = SimulationClock::new();
+        assert_close(clock.tick(3.0) as f64, MAX_FRAME_TIME as f64);
+    }
+
+    #[test]
+    fn speed_stays_within_its_range_and_limit() {
+        let mut clock = SimulationClock::new();
+        for _ in 0..SPEEDS.len() {
+            clock.slower();
+        }
+        assert_eq!(clock.speed(), 0.1);
+        for _ in 0..SPEEDS.len() {
+            clock.faster();
+        }
+        assert_eq!(clock.speed(), 1000.0);
+
+        clock.limit_speed(Some(100.0));
+        assert_eq!(clock.speed(), 100.0);
+        clock.faster();
+        assert_eq!(clock.speed(), 100.0);
+        clock.limit_speed(None);
+        clock.faster();
+        assert_eq!(clock.speed(), 250.0);
+    }
+
+    #[test]
+    fn shader_time_keeps_advancing_at_large_times() {
+        let mut clock = SimulationClock::new();
+        clock.jump_to(5.0e6);
+        let before = clock.shader_time();
+        clock.tick(1.0 / 60.0);
+        let advanced = clock.shader_time() - before;
+        assert!((advanced - 1.0 / 60.0).abs() < 1e-4, "avanzó {}", advanced);
+    }
+
+    #[test]
+    fn shader_time_is_continuous_across_zero_in_reverse() {
+        let mut clock = SimulationClock::new();
+        clock.jump_to(0.01);
+        clock.toggle_reverse();
+        let before = clock.shader_time();
+        clock.tick(0.02);
+        let after = clock.shader_time();
+        assert!(after < 0.0);
+        assert!((after - before + 0.02).abs() < 1e-6, "retrocedió {}", before - after);
+    }
+}
@@ -0,0 +1,101 @@
+// ============================================================================
+// CAPA DE NUBES
+// Cáscara esférica translúcida que gira a su propio ritmo sobre la superficie
+// y proyecta sombras suaves sobre ella
+// ============================================================================
+
+use crate::vector::Vector3;
+use crate::shaders::{ShaderColor, ShaderUniforms, PlanetShader, RenderLayer, NoiseNode, NoiseNodeExt, BasisNode, NoiseBasis, CombineOp, smoothstep, mix_color};
+
+/// Huella con la que se evalúan las nubes para la sombra: al descartar las
+/// octavas finas la sombra sale difuminada
+const SHADOW_BLUR: f32 = 0.06;
+
+/// Cuánta luz bloquea una nube completamente densa
+const SHADOW_STRENGTH: f32 = 0.6;
+
+pub struct CloudShader {
+    field: Box<dyn NoiseNode>,
+    /// Fracción aproximada del cielo cubierta, en [0, 1]
+    coverage: f32,
+}
+
+impl CloudShader {
+    pub fn new(seed: u32, coverage: f32) -> Self {
+        // Cúmulos hinchados (billow) con bordes deshilachados por la
+        // deformación del dominio. Rango [0, 1]
+        let cumulus = BasisNode::new(seed, NoiseBasis::Simplex)
+            .billow(5, 2.0, 0.5)
+            .warp(BasisNode::new(seed.wrapping_add(1), NoiseBasis::Simplex).fbm(2, 2.0, 0.5), 0.4, 1)
+            .scale(Vector3::new(3.0, 3.0, 3.0))
+            .remap(-0.65, 0.05, 0.0, 1.0)
+            .clamp(0.0, 1.0);
+
+        // Sistemas de tiempo a gran escala: frentes nubosos y cielos despejados.
+        // Factor centrado en 1 para que `coverage` siga siendo aproximada
+        let weather = BasisNode::new(seed.wrapping_add(2), NoiseBasis::Simplex)
+            .fbm(2, 2.0, 0.5)
+            .scale(Vector3::new(1.2, 1.2, 1.2))
+            .remap(-0.3, 0.3, 0.6, 1.4)
+            .clamp(0.6, 1.4);
+
+        let field = cumulus
+            .combine(weather, CombineOp::Multiply)
+            .clamp(0.0, 1.0)
+            .boxed();
+
+        CloudShader {
+            field,
+            coverage: coverage.clamp(0.0, 1.0),
+        }
+    }
+
+    /// Capa de nubes a `radius_scale` veces el radio de la superficie
+    pub fn layer(self, radius_scale: f32, rotation_speed: f32) -> RenderLayer {
+        RenderLayer {
+            shader: Box::new(self),
+            radius_scale,
+            rotation_speed,
+        }
+    }
+
+    /// Densidad de nubes en una dirección de la esfera, en [0, 1]
+    fn density(&self, direction: Vector3, footprint: f32) -> f32 {
+        let threshold = 1.0 - self.coverage;
+        smoothstep(threshold - 0.12, threshold + 0.12, self.field.eval_filtered(direction, footprint))
+    }
+}
+
+impl PlanetShader for CloudShader {
+    fn vertex_shader(&self, position: Vector3, normal: Vector3, _uv: (f32, f32), _uniforms: &ShaderUniforms) -> (Vector3, Vector3) {
+        (position, normal)
+    }
+
+    fn fragment_shader(&self, position: Vector3, normal: Vector3, _uv: (f32, f32), uniforms: &ShaderUniforms) -> ShaderColor {
+        let object_position = uniforms.object_position(position);
+        let direction = object_position.normalize();
+        let footprint = uniforms.footprint(position) / object_position.length();
+        let density = self.density(direction, footprint);
+
+        // Las nubes gruesas se ven algo más grises por dentro
+        let thin = ShaderColor::from_rgb(255, 255, 255);
+        let thick = ShaderColor::from_rgb(200, 205, 215);
+        let base_color = mix_color(thin, thick, smoothstep(0.6, 1.0, density));
+
+        // Luz envolvente: la luz se difunde un poco más allá del terminador
+        let light_dir = uniforms.light_direction.normalize();
+        let wrap = ((normal.dot(&light_dir) + 0.2) / 1.2).max(0.0);
+        let lighting = 0.06 + wrap * 0.94;
+
+        ShaderColor::new(
+            (base_color.r * lighting).clamp(0.0, 1.0),
+            (base_color.g * lighting).clamp(0.0, 1.0),
+            (base_color.b * lighting).clamp(0.0, 1.0),
+            density * 0.85,
+        )
+    }
+
+    fn shadow_opacity(&self, object_position: Vector3, _uniforms: &ShaderUniforms) -> f32 {
+        self.density(object_position.normalize(), SHADOW_BLUR) * SHADOW_STRENGTH
+    }
+}
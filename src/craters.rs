@@ -0,0 +1,186 @@
+// ============================================================================
+// CAMPO DE CRÁTERES
+// Cráteres colocados sobre la esfera con una distribución de tamaños en ley de
+// potencia (muchos pequeños, pocos grandes). Cada uno tiene un perfil físico:
+// cuenco, borde elevado, manto de eyección y, los grandes, fondo plano con pico
+// central. Los más recientes conservan rayos de eyección brillantes.
+// Sirve como desplazamiento del relieve y como máscara de albedo
+// ============================================================================
+
+use crate::vector::Vector3;
+use crate::shaders::{NoiseSource, NoiseBasis, smoothstep, mix, tangent_frame, seeded_random, seeded_direction};
+
+/// Radio angular a partir del cual un cráter es complejo (fondo plano y pico central)
+const COMPLEX_RADIUS: f32 = 0.12;
+
+/// Altura del borde respecto a la profundidad del cuenco
+const RIM_HEIGHT: f32 = 0.3;
+
+/// Relación profundidad/diámetro de los cráteres simples y de los complejos
+const SIMPLE_DEPTH_RATIO: f32 = 0.2;
+const COMPLEX_DEPTH_RATIO: f32 = 0.1;
+
+/// Edad por debajo de la cual un cráter conserva sus rayos (edades en [0, 1])
+const FRESH_AGE: f32 = 0.2;
+
+/// Alcance del manto de eyección y de los rayos más largos, en radios del cráter
+const BLANKET_REACH: f32 = 3.0;
+const RAY_REACH: f32 = 7.0;
+
+struct Crater {
+    center: Vector3,
+    /// Base tangente en el centro para medir el azimut de los rayos
+    tangent: Vector3,
+    bitangent: Vector3,
+    /// Radio angular del borde (radianes)
+    radius: f32,
+    /// Profundidad del cuenco en radios del cuerpo
+    depth: f32,
+    /// 0 = recién formado, 1 = muy erosionado
+    age: f32,
+    /// Coseno del ángulo más allá del cual el cráter no influye
+    cos_reach: f32,
+    /// Semilla de los rayos
+    ray_offset: f32,
+}
+
+/// Resultado de muestrear el campo en una dirección
+#[derive(Debug, Clone, Copy)]
+pub struct CraterSample {
+    /// Desplazamiento radial en radios del cuerpo (negativo en los cuencos)
+    pub height: f32,
+    /// 1 dentro del cuenco de algún cráter, 0 fuera
+    pub interior: f32,
+    /// Material fresco y claro: mantos y rayos de los cráteres recientes, en [0, 1]
+    pub ejecta: f32,
+}
+
+pub struct CraterField {
+    noise: NoiseSource,
+    /// Ordenados del más antiguo al más reciente: los nuevos se superponen
+    craters: Vec<Crater>,
+}
+
+impl CraterField {
+    /// `count` cráteres con radios angulares entre `min_radius` y `max_radius`.
+    /// `slope` es el exponente de la distribución acumulada N(> r) ∝ r^-slope
+    pub fn new(seed: u32, count: u32, min_radius: f32, max_radius: f32, slope: f32) -> Self {
+        let min_radius = min_radius.max(0.001);
+        let max_radius = max_radius.max(min_radius);
+        let slope = slope.max(0.1);
+
+        let mut craters: Vec<Crater> = (0..count)
+            .map(|i| {
+                let base = i * 5;
+                let center = seeded_direction(seed, base);
+                let (tangent, bitangent) = tangent_frame(center);
+
+                // Ley de potencia truncada por muestreo de la inversa de la acumulada
+                let u = seeded_random(seed, base + 2);
+                let low = min_radius.powf(-slope);
+                let high = max_radius.powf(-slope);
+                let radius = (low - u * (low - high)).powf(-1.0 / slope);
+
+                let ratio = if radius > COMPLEX_RADIUS { COMPLEX_DEPTH_RATIO } else { SIMPLE_DEPTH_RATIO };
+                let age = seeded_random(seed, base + 3);
+                let reach = if age < FRESH_AGE { RAY_REACH } else { BLANKET_REACH };
+
+                Crater {
+                    center,
+                    tangent,
+                    bitangent,
+                    radius,
+                    depth: ratio * 2.0 * radius,
+                    age,
+                    cos_reach: (radius * reach).min(std::f32::consts::PI).cos(),
+                    ray_offset: seeded_random(seed, base + 4) * 100.0,
+                }
+            })
+            .collect();
+        craters.sort_by(|a, b| b.age.total_cmp(&a.age));
+
+        CraterField {
+            noise: NoiseSource::new(seed),
+            craters,
+        }
+    }
+
+    /// Perfil de altura en unidades de la profundidad del cuenco, según la
+    /// distancia al centro en radios del cráter (1 = cresta del borde)
+    fn profile(distance: f32, complex: bool) -> f32 {
+        if distance < 1.0 {
+            let bowl = if complex {
+                // Fondo plano por el colapso de las paredes y pico central de rebote
+                let floor = mix(-0.75, 0.0, smoothstep(0.4, 1.0, distance));
+                floor + 0.5 * (-(distance / 0.15).powi(2)).exp()
+            } else {
+                distance * distance - 1.0
+            };
+            bowl + RIM_HEIGHT * (-((distance - 1.0) / 0.15).powi(2)).exp()
+        } else {
+            // Manto de eyección: su espesor cae aproximadamente como r^-3
+            RIM_HEIGHT * distance.powi(-3) * smoothstep(BLANKET_REACH, BLANKET_REACH - 1.0, distance)
+        }
+    }
+
+    /// Claridad de los rayos y del manto de un cráter reciente, en [0, 1]
+    fn rays(&self, crater: &Crater, direction: Vector3, distance: f32) -> f32 {
+        let azimuth = direction.dot(&crater.bitangent).atan2(direction.dot(&crater.tangent));
+        let streak = self.noise.fbm3d_basis(
+            NoiseBasis::Simplex,
+            azimuth.cos() * 6.0,
+            azimuth.sin() * 6.0,
+            crater.ray_offset,
+            2,
+        );
+        // Cada rayo llega a una distancia distinta
+        let length = mix(BLANKET_REACH, RAY_REACH, (streak + 0.5).clamp(0.0, 1.0));
+        let rays = smoothstep(0.05, 0.3, streak) * smoothstep(length, 1.0, distance);
+        let blanket = (-((distance - 1.0) / 0.5).powi(2)).exp() * 0.7;
+
+        let freshness = 1.0 - crater.age / FRESH_AGE;
+        rays.max(blanket) * freshness
+    }
+
+    /// Muestrea el campo en una dirección de la esfera unitaria. Los cráteres
+    /// menores que `footprint` (tamaño del píxel o de la malla, en radianes) se
+    /// desvanecen en lugar de producir aliasing
+    pub fn sample(&self, direction: Vector3, footprint: f32) -> CraterSample {
+        self.evaluate(direction, footprint, true)
+    }
+
+    /// Solo el desplazamiento radial, en radios del cuerpo. Se salta los rayos,
+    /// que son la parte cara y no cambian el relieve
+    pub fn height(&self, direction: Vector3, footprint: f32) -> f32 {
+        self.evaluate(direction, footprint, false).height
+    }
+
+    fn evaluate(&self, direction: Vector3, footprint: f32, with_ejecta: bool) -> CraterSample {
+        let mut sample = CraterSample { height: 0.0, interior: 0.0, ejecta: 0.0 };
+
+        for crater in &self.craters {
+            let cos_angle = direction.dot(&crater.center);
+            if cos_angle < crater.cos_reach {
+                continue;
+            }
+            let visibility = smoothstep(footprint, footprint * 3.0, crater.radius);
+            if visibility <= 0.0 {
+                continue;
+            }
+
+            let distance = cos_angle.clamp(-1.0, 1.0).acos() / crater.radius;
+            let height = Self::profile(distance, crater.radius > COMPLEX_RADIUS) * crater.depth;
+
+            // Un cráter nuevo borra el relieve anterior dentro de su borde
+            let erase = smoothstep(1.15, 0.85, distance) * visibility;
+            sample.height = sample.height * (1.0 - erase) + height * visibility;
+            sample.interior = sample.interior.max(smoothstep(1.0, 0.8, distance) * visibility);
+
+            if with_ejecta && crater.age < FRESH_AGE {
+                sample.ejecta = sample.ejecta.max(self.rays(crater, direction, distance) * visibility);
+            }
+        }
+
+        sample
+    }
+}
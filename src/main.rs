@@ -4,114 +4,718 @@ mod orbital_camera;
 mod sphere;
 mod shaders;
 mod planets;  
+mod rings;
+mod atmosphere;
+mod clouds;
+mod aurora;
+mod asteroid;
+mod craters;
+mod orbit;
+mod gravity;
+mod scene_graph;
+mod quaternion;
+mod solar_system;
+mod clock;
 mod ui;  
 
+use std::fs;
+use std::path::Path;
 use raylib::prelude::*;
 use vector::Vector3;
 use orbital_camera::OrbitalCamera;
-use sphere::Mesh;
-use shaders::{PlanetShader, ShaderUniforms, ShaderColor};
-use planets::{RockyPlanetShader, GasGiantShader, CrystalPlanetShader, LavaPlanetShader, SaturnShader};
-use ui::render_ui;  
-
-enum PlanetType {
-    Rocky,
-    GasGiant,
-    Crystal,
-    Nebula,
-    Saturn,
-}
+use transform::Transform4x4;
+use sphere::{Mesh, PLANET_RADIUS};
+use shaders::{PlanetShader, ShaderUniforms, ShaderColor, ShaderParams, HaloStyle, NoiseSource, ColorRamp, next_seed};
+use planets::PlanetDescriptor;
+use rings::RingShader;
+use planets::registry::ShaderFactory;
+use orbit::OrbitalElements;
+use gravity::{GravitySimulation, Integrator};
+use solar_system::BodyPlacement;
+use scene_graph::{NodeId, SceneGraph};
+use quaternion::Quaternion;
+use clock::SimulationClock;
+use ui::{render_ui, GravityReadout, SimulationStatus, PLANETS_PER_PAGE};
 
 struct Planet {
     mesh: Mesh,
     shader: Box<dyn PlanetShader>,
+    /// Malla de los anillos, si el shader los tiene
+    ring_mesh: Option<Mesh>,
+    /// Ángulo de giro de cada capa de `shader.layers()`
+    layer_rotations: Vec<f32>,
+    /// Id del registro; da nombre a sus paletas externas
+    id: &'static str,
+    factory: ShaderFactory,
+    params: ShaderParams,
     rotation: f32,
     rotation_speed: f32,
+    /// Inclinación del eje de giro respecto a la vertical (radianes)
+    axial_tilt: f32,
+    /// Ángulo actual y velocidad de la precesión del eje alrededor de la vertical
+    precession: f32,
+    precession_rate: f32,
+    /// Ángulo de la precesión en t = 0
+    axis_longitude: f32,
+    /// Tamaño del cuerpo en la vista de sistema (1 = radio 0.5)
+    scale: f32,
+    /// Nodos que lo colocan en el grafo de escena
+    nodes: BodyNodes,
+    /// Masa en unidades con G = 1
+    mass: f32,
+    /// Cuerpo central alrededor del que orbita (posición en `planets`)
+    parent: Option<usize>,
+    orbit: Option<OrbitalElements>,
+    /// Tiempo de simulación transcurrido sobre la órbita
+    orbit_time: f64,
+    /// Posición relativa al cuerpo central en `orbit_time`
+    orbit_offset: Vector3,
+}
+
+/// Nodos del grafo de escena de un cuerpo
+#[derive(Debug, Clone, Copy)]
+struct BodyNodes {
+    /// Posición sobre la órbita, colgado del nodo de órbita de su cuerpo central
+    /// (una luna sigue a su planeta sin heredar su giro ni su escala)
+    orbit: NodeId,
+    /// Marco del cuerpo con su escala; sus hijos están en espacio local, donde
+    /// el planeta mide 0.5 de radio
+    body: NodeId,
+    /// Eje de giro: inclinación y precesión
+    axis: NodeId,
+    /// Giro de la superficie alrededor del eje
+    spin: NodeId,
+    /// Anillos, en el plano ecuatorial del eje (no giran con la superficie)
+    rings: NodeId,
+}
+
+impl BodyNodes {
+    fn new(graph: &mut SceneGraph) -> Self {
+        let orbit = graph.add_node(None);
+        let body = graph.add_node(Some(orbit));
+        let axis = graph.add_node(Some(body));
+        BodyNodes {
+            orbit,
+            body,
+            axis,
+            spin: graph.add_node(Some(axis)),
+            rings: graph.add_node(Some(axis)),
+        }
+    }
+}
+
+/// Uniforms expresados en el marco de un cuerpo (`frame` lleva su espacio local
+/// al mundo). Todos los shaders, capas y anillos siguen suponiendo un planeta
+/// en el origen
+fn local_uniforms(frame: &Transform4x4, uniforms: &ShaderUniforms) -> ShaderUniforms {
+    let world_to_frame = frame.inverse();
+    ShaderUniforms {
+        camera_position: world_to_frame.apply_to_vector(&uniforms.camera_position),
+        light_direction: world_to_frame.apply_to_direction(&uniforms.light_direction).normalize(),
+        ..*uniforms
+    }
 }
 
 impl Planet {
-    fn new(planet_type: PlanetType) -> Self {
-        let mesh = Mesh::from_obj("src/sphere.obj")
-            .expect("ERROR CRÍTICO: No se pudo cargar el archivo 'src/sphere.obj'. Asegúrate de que el archivo exista.");
-        
-        let (shader, rotation_speed): (Box<dyn PlanetShader>, f32) = match planet_type {
-            PlanetType::Rocky => (Box::new(RockyPlanetShader), 0.5),
-            PlanetType::GasGiant => (Box::new(GasGiantShader), 0.8),
-            PlanetType::Crystal => (Box::new(CrystalPlanetShader), 1.2),
-            PlanetType::Nebula => (Box::new(LavaPlanetShader), 1.5),
-            PlanetType::Saturn => (Box::new(SaturnShader), 0.6),
-        };
+    fn new(descriptor: &PlanetDescriptor, graph: &mut SceneGraph) -> Self {
+        let params = descriptor.default_params.clone();
+        let mut shader = descriptor.create_shader(&params);
+        load_palettes(descriptor.id, shader.as_mut());
+        let mesh = shader.mesh().unwrap_or_else(|| {
+            Mesh::from_obj("src/sphere.obj")
+                .expect("ERROR CRÍTICO: No se pudo cargar el archivo 'src/sphere.obj'. Asegúrate de que el archivo exista.")
+        });
+        let ring_mesh = shader.rings().map(RingShader::mesh);
+        let layer_rotations = vec![0.0; shader.layers().len()];
         
         Planet {
             mesh,
             shader,
+            ring_mesh,
+            layer_rotations,
+            id: descriptor.id,
+            factory: descriptor.factory,
+            params,
             rotation: 0.0,
-            rotation_speed,
+            rotation_speed: descriptor.rotation_speed,
+            axial_tilt: descriptor.axial_tilt,
+            precession: 0.0,
+            precession_rate: descriptor.precession_rate,
+            axis_longitude: descriptor.axis_longitude,
+            scale: descriptor.scene_scale,
+            nodes: BodyNodes::new(graph),
+            mass: 1.0,
+            parent: None,
+            orbit: None,
+            orbit_time: 0.0,
+            orbit_offset: Vector3::new(0.0, 0.0, 0.0),
+        }
+    }
+    
+    /// Lleva el cuerpo al instante `time` del reloj de simulación. Giro,
+    /// precesión, capas y órbita son funciones del tiempo, así que avanzar,
+    /// retroceder o saltar dan el mismo resultado
+    fn set_time(&mut self, time: f64) {
+        // Ángulo reducido a una vuelta en f64: a tiempos grandes el f32 se degrada
+        let angle = |speed: f32| (speed as f64 * time).rem_euclid(std::f64::consts::TAU) as f32;
+        
+        // Rotación sobre su propio eje, que a su vez precesa
+        self.rotation = angle(self.rotation_speed);
+        self.precession = self.axis_longitude + angle(self.precession_rate);
+        
+        // Cada capa gira a su propio ritmo (las nubes se desplazan sobre el suelo)
+        for (rotation, layer) in self.layer_rotations.iter_mut().zip(self.shader.layers()) {
+            *rotation = angle(layer.rotation_speed);
+        }
+        
+        // Posición sobre la órbita alrededor de su cuerpo central
+        self.orbit_time = time;
+        if let Some(orbit) = &self.orbit {
+            self.orbit_offset = orbit.position_at(self.orbit_time);
         }
     }
     
-    fn update(&mut self, dt: f32) {
-        // Rotación sobre su propio eje
-        self.rotation += self.rotation_speed * dt;
+    /// Coloca el cuerpo en el sistema: masa y, si la tiene, órbita alrededor de
+    /// otro. `parent_orbit` es el nodo de órbita de ese cuerpo central
+    fn set_placement(&mut self, placement: &BodyPlacement, parent_orbit: Option<NodeId>, graph: &mut SceneGraph) -> Result<(), String> {
+        self.mass = placement.mass;
+        self.parent = placement.orbit.map(|(parent, _)| parent);
+        self.orbit = placement.orbit.map(|(_, elements)| elements);
+        self.orbit_offset = self.orbit.map_or(Vector3::new(0.0, 0.0, 0.0), |orbit| orbit.position_at(self.orbit_time));
+        graph.set_parent(self.nodes.orbit, parent_orbit)
+    }
+    
+    /// Orientación del eje de giro en el marco del cuerpo: inclinado
+    /// `axial_tilt` y girado `precession` alrededor de la vertical
+    fn axis_orientation(&self) -> Quaternion {
+        Quaternion::from_euler(self.precession, self.axial_tilt, 0.0)
+    }
+    
+    /// Vuelca el estado del cuerpo en sus nodos: posición orbital, escala, eje
+    /// y giro. Los anillos siguen al eje sin rotación propia
+    fn sync_nodes(&self, graph: &mut SceneGraph) {
+        graph.node_mut(self.nodes.orbit).translation = self.orbit_offset;
+        graph.node_mut(self.nodes.body).scale = self.scale;
+        graph.node_mut(self.nodes.axis).rotation = self.axis_orientation();
+        graph.node_mut(self.nodes.spin).rotation = Quaternion::from_axis_angle(Vector3::new(0.0, 1.0, 0.0), self.rotation);
+    }
+    
+    /// Matriz de modelo de la capa `index`: el eje inclinado del planeta, su
+    /// propio giro alrededor de él y su radio
+    fn layer_model_matrix(&self, index: usize) -> Transform4x4 {
+        let layer = &self.shader.layers()[index];
+        self.axis_orientation().to_matrix()
+            .compose(&transform::build_y_axis_rotation(self.layer_rotations[index]))
+            .compose(&transform::build_uniform_scale(layer.radius_scale))
+    }
+    
+    /// Radio de la esfera que contiene el cuerpo y sus anillos, en espacio local
+    fn extent(&self) -> f32 {
+        let rings = self.ring_mesh.as_ref().map_or(0.0, Mesh::bounding_radius);
+        self.mesh.bounding_radius().max(rings)
+    }
+    
+    /// Distancia mínima entre la cámara y el centro del cuerpo para que
+    /// `render_scene` no lo descarte por cruzar el plano cercano
+    fn near_clearance(&self) -> f32 {
+        self.extent() * self.scale + NEAR_PLANE * 2.0
+    }
+    
+    /// Pasa a la siguiente semilla de la secuencia y reconstruye el shader
+    fn randomize_seed(&mut self) {
+        let seed = next_seed(self.params.seed());
+        self.params.set("seed", seed as f32);
+        self.shader = (self.factory)(&self.params);
+        load_palettes(self.id, self.shader.as_mut());
+        // Los cuerpos con malla propia cambian de forma con la semilla
+        if let Some(mesh) = self.shader.mesh() {
+            self.mesh = mesh;
+        }
+        self.ring_mesh = self.shader.rings().map(RingShader::mesh);
+        self.layer_rotations.resize(self.shader.layers().len(), 0.0);
     }
 }
 
+/// Carpeta de las paletas externas: `<id>_<n>.txt` (formato de
+/// `ColorRamp::to_text`) o `<id>_<n>.png` (tira de gradiente) sustituyen a la
+/// rampa `n` del planeta `id`
+const PALETTE_DIR: &str = "palettes";
+
+/// Sustituye las rampas del shader por las paletas externas que existan; si
+/// hay texto y PNG para la misma rampa, manda el texto
+fn load_palettes(id: &str, shader: &mut dyn PlanetShader) {
+    for (index, ramp) in shader.color_ramps_mut().into_iter().enumerate() {
+        let base = format!("{}/{}_{}", PALETTE_DIR, id, index);
+        let text_path = format!("{}.txt", base);
+        let png_path = format!("{}.png", base);
+
+        let palette = if Path::new(&text_path).exists() {
+            fs::read_to_string(&text_path)
+                .map_err(|e| format!("No se pudo leer la paleta: {}", e))
+                .and_then(|text| ColorRamp::parse(&text))
+        } else if Path::new(&png_path).exists() {
+            // La tira solo aporta los colores: se conserva la interpolación de la rampa
+            ColorRamp::from_gradient_png(&png_path, ramp.interpolation, ramp.color_space)
+        } else {
+            continue;
+        };
+
+        match palette {
+            Ok(palette) => *ramp = palette,
+            Err(e) => eprintln!("Paleta '{}' ignorada: {}", base, e),
+        }
+    }
+}
+
+/// Escribe las rampas del shader en `PALETTE_DIR` como texto, listas para
+/// editarlas y recargarlas con una nueva semilla. Devuelve cuántas se guardaron
+fn export_palettes(id: &str, shader: &mut dyn PlanetShader) -> Result<usize, String> {
+    let ramps = shader.color_ramps_mut();
+    fs::create_dir_all(PALETTE_DIR).map_err(|e| format!("No se pudo crear la carpeta de paletas: {}", e))?;
+    for (index, ramp) in ramps.iter().enumerate() {
+        let path = format!("{}/{}_{}.txt", PALETTE_DIR, id, index);
+        fs::write(&path, ramp.to_text()).map_err(|e| format!("No se pudo escribir '{}': {}", path, e))?;
+    }
+    Ok(ramps.len())
+}
+
+/// Devuelve la posición (0-8) de la tecla numérica 1-9 presionada en este frame
+fn pressed_number_key(rl: &RaylibHandle) -> Option<usize> {
+    const NUMBER_KEYS: [KeyboardKey; PLANETS_PER_PAGE] = [
+        KeyboardKey::KEY_ONE,
+        KeyboardKey::KEY_TWO,
+        KeyboardKey::KEY_THREE,
+        KeyboardKey::KEY_FOUR,
+        KeyboardKey::KEY_FIVE,
+        KeyboardKey::KEY_SIX,
+        KeyboardKey::KEY_SEVEN,
+        KeyboardKey::KEY_EIGHT,
+        KeyboardKey::KEY_NINE,
+    ];
+    
+    NUMBER_KEYS.iter().position(|key| rl.is_key_pressed(*key))
+}
+
+/// Procesa el teclado mientras se escribe el instante de un salto: cifras,
+/// punto y signo se añaden, RETROCESO borra y ENTER salta (si lo escrito no
+/// es un número, se vacía). Devuelve `true` si el salto se hizo
+fn read_jump_entry(rl: &mut RaylibHandle, entry: &mut String, clock: &mut SimulationClock) -> bool {
+    while let Some(character) = rl.get_char_pressed() {
+        if character.is_ascii_digit() || character == '.' || (character == '-' && entry.is_empty()) {
+            entry.push(character);
+        }
+    }
+    if rl.is_key_pressed(KeyboardKey::KEY_BACKSPACE) {
+        entry.pop();
+    }
+    if !rl.is_key_pressed(KeyboardKey::KEY_ENTER) {
+        return false;
+    }
+    match entry.parse::<f64>() {
+        Ok(time) => {
+            clock.jump_to(time);
+            true
+        }
+        Err(_) => {
+            entry.clear();
+            false
+        }
+    }
+}
+
+/// Emisión por debajo de este valor no genera bloom
+const BLOOM_THRESHOLD: f32 = 0.25;
+
+/// Mancha de luz que deja un triángulo emisivo visible
+struct BloomSprite {
+    center: Vector2,
+    radius: f32,
+    color: ShaderColor,
+    strength: f32,
+}
+
+/// Plano cercano de la proyección: lo que queda más cerca se descarta
+const NEAR_PLANE: f32 = 0.1;
+
+/// Matrices de cámara, proyección y viewport compartidas por todas las pasadas del frame
+struct ScreenProjection {
+    view_matrix: Transform4x4,
+    proj_matrix: Transform4x4,
+    viewport_matrix: Transform4x4,
+    vertical_fov: f32,
+    width: i32,
+    height: i32,
+}
+
+impl ScreenProjection {
+    fn new(camera: &OrbitalCamera, width: i32, height: i32) -> Self {
+        let vertical_fov = 45.0_f32.to_radians();
+        ScreenProjection {
+            view_matrix: camera.get_transform_matrix(),
+            proj_matrix: transform::build_perspective_projection(
+                vertical_fov,
+                width as f32 / height as f32,
+                NEAR_PLANE,
+                100.0,
+            ),
+            viewport_matrix: transform::build_viewport_transform(0.0, 0.0, width as f32, height as f32),
+            vertical_fov,
+            width,
+            height,
+        }
+    }
+    
+    /// Lleva un punto del mundo a coordenadas de pantalla
+    fn project(&self, point: &Vector3) -> Vector3 {
+        self.viewport_matrix.apply_to_vector(&self.proj_matrix.apply_to_vector(&self.view_matrix.apply_to_vector(point)))
+    }
+    
+    fn is_on_screen(&self, point: &Vector3) -> bool {
+        point.x >= 0.0 && point.x < self.width as f32 && point.y >= 0.0 && point.y < self.height as f32
+    }
+    
+    /// La misma proyección vista desde el marco de un cuerpo: los puntos en su
+    /// espacio local se colocan y escalan antes de pasar por la cámara
+    fn for_body(&self, frame: &Transform4x4) -> Self {
+        ScreenProjection {
+            view_matrix: self.view_matrix.compose(frame),
+            ..*self
+        }
+    }
+}
+
+/// Velocidad máxima del reloj con la gravedad de N cuerpos: por encima la
+/// física no llega a integrar todo el tiempo de cada fotograma
+const GRAVITY_MAX_SPEED: f32 = 100.0;
+
+/// Distancia de la cámara a un cuerpo enfocado, en radios de la vista individual
+const FOCUS_DISTANCE: f32 = 5.0;
+
+/// Suma `value` a lo largo de la cadena de cuerpos centrales de `index`:
+/// lo relativo a cada órbita se acumula hasta el centro del sistema
+fn sum_along_parents(planets: &[Planet], index: usize, value: impl Fn(&Planet) -> Vector3) -> Vector3 {
+    let mut total = Vector3::new(0.0, 0.0, 0.0);
+    let mut current = Some(index);
+    // El límite de pasos protege de ciclos en la jerarquía
+    for _ in 0..planets.len() {
+        let Some(body) = current else { break };
+        total = total + value(&planets[body]);
+        current = planets[body].parent;
+    }
+    total
+}
+
+/// Simulación de N cuerpos que parte de las posiciones y velocidades actuales
+/// de las órbitas de Kepler, así el cambio de modo no da saltos
+fn start_gravity(planets: &[Planet], graph: &SceneGraph, integrator: Integrator) -> GravitySimulation {
+    let velocities = (0..planets.len())
+        .map(|index| sum_along_parents(planets, index, |planet| {
+            planet.orbit.map_or(Vector3::new(0.0, 0.0, 0.0), |orbit| orbit.velocity_at(planet.orbit_time))
+        }))
+        .collect();
+    GravitySimulation::new(
+        planets.iter().map(|planet| graph.world_position(planet.nodes.body)).collect(),
+        velocities,
+        planets.iter().map(|planet| planet.mass).collect(),
+        integrator,
+    )
+}
+
+/// Dibuja todos los cuerpos en su lugar. Sin Z-buffer, cada cuerpo se dibuja
+/// entero del más lejano al más cercano a la cámara, así los cercanos tapan a
+/// los lejanos. Los que quedan detrás de la cámara se omiten
+fn render_scene(
+    planets: &[Planet],
+    graph: &SceneGraph,
+    star: Option<usize>,
+    projection: &ScreenProjection,
+    uniforms: &ShaderUniforms,
+    show_axis: bool,
+    rl: &mut RaylibDrawHandle,
+) {
+    // La tercera fila de la vista es el eje hacia atrás de la cámara
+    let view = &projection.view_matrix.elements;
+    let forward = Vector3::new(-view[2][0], -view[2][1], -view[2][2]);
+    let camera = uniforms.camera_position;
+    let position = |index: usize| graph.world_position(planets[index].nodes.body);
+    let star_position = star.map(position);
+    
+    let mut order: Vec<(usize, f32)> = (0..planets.len())
+        .filter(|&index| {
+            let depth = forward.dot(&(position(index) - camera));
+            depth - planets[index].extent() * planets[index].scale > NEAR_PLANE
+        })
+        .map(|index| (index, (position(index) - camera).length()))
+        .collect();
+    order.sort_by(|a, b| b.1.total_cmp(&a.1));
+    
+    for (index, _) in order {
+        let planet = &planets[index];
+        // La luz llega desde la estrella; la propia estrella conserva la dirección fija
+        let light_direction = match star_position {
+            Some(star_position) if Some(index) != star => (star_position - position(index)).normalize(),
+            _ => uniforms.light_direction,
+        };
+        let uniforms = ShaderUniforms { light_direction, ..*uniforms };
+        render_body(planet, graph, graph.world_matrix(planet.nodes.body), projection, &uniforms, show_axis, rl);
+    }
+}
+
+/// Dibuja un cuerpo completo en el marco indicado: halo, anillos lejanos,
+/// superficie, capas, anillos cercanos y su bloom. La superficie y los anillos
+/// toman su matriz de modelo de sus nodos, relativa al marco del cuerpo
+fn render_body(
+    planet: &Planet,
+    graph: &SceneGraph,
+    frame: &Transform4x4,
+    projection: &ScreenProjection,
+    uniforms: &ShaderUniforms,
+    show_axis: bool,
+    rl: &mut RaylibDrawHandle,
+) {
+    let projection = projection.for_body(frame);
+    let uniforms = local_uniforms(frame, uniforms);
+    
+    if let Some(halo) = planet.shader.halo(&uniforms) {
+        render_halo(planet, &halo, &projection, uniforms.time, rl);
+    }
+    
+    if show_axis {
+        render_spin_axis(planet, graph, &projection, &uniforms, rl, true);
+    }
+    render_rings(planet, graph, &projection, &uniforms, rl, true);
+    let mut bloom = render_planet_software(planet, graph, &projection, &uniforms, rl);
+    bloom.extend(render_layers(planet, &projection, &uniforms, rl));
+    render_rings(planet, graph, &projection, &uniforms, rl, false);
+    if show_axis {
+        render_spin_axis(planet, graph, &projection, &uniforms, rl, false);
+    }
+    render_bloom(&bloom, rl);
+}
+
+/// Dibuja los tramos del eje de giro que asoman por los polos: los que apuntan
+/// lejos de la cámara (`far_side`) antes del planeta, para que este los tape
+fn render_spin_axis(
+    planet: &Planet,
+    graph: &SceneGraph,
+    projection: &ScreenProjection,
+    uniforms: &ShaderUniforms,
+    rl: &mut RaylibDrawHandle,
+    far_side: bool,
+) {
+    let axis = graph.matrix_relative_to(planet.nodes.axis, planet.nodes.body)
+        .apply_to_direction(&Vector3::new(0.0, 1.0, 0.0))
+        .normalize();
+    let surface = planet.mesh.bounding_radius();
+    let center_distance = uniforms.camera_position.length();
+    
+    // Norte en rojizo y sur en azulado
+    let poles = [(axis, Color::new(255, 120, 100, 220)), (axis * -1.0, Color::new(120, 160, 255, 220))];
+    for (direction, color) in poles {
+        let tip = direction * (surface * 1.6);
+        if ((tip - uniforms.camera_position).length() > center_distance) != far_side {
+            continue;
+        }
+        let start = projection.project(&(direction * surface));
+        let end = projection.project(&tip);
+        if projection.is_on_screen(&start) && projection.is_on_screen(&end) {
+            rl.draw_line_ex(Vector2::new(start.x, start.y), Vector2::new(end.x, end.y), 2.0, color);
+        }
+    }
+}
+
+/// Dibuja el planeta y devuelve los triángulos emisivos visibles para el bloom
 fn render_planet_software(
     planet: &Planet,
-    camera: &OrbitalCamera,
+    graph: &SceneGraph,
+    projection: &ScreenProjection,
     uniforms: &ShaderUniforms,
     rl: &mut RaylibDrawHandle,
-    width: i32,
-    height: i32,
+) -> Vec<BloomSprite> {
+    let model_matrix = graph.matrix_relative_to(planet.nodes.spin, planet.nodes.body);
+    // Inversa de cada capa, una vez por fotograma y no por vértice
+    let layer_inverses = (0..planet.shader.layers().len())
+        .map(|index| planet.layer_model_matrix(index).inverse())
+        .collect();
+    let surface = LayerShadowedSurface { planet, layer_inverses };
+    render_mesh(&planet.mesh, &surface, &model_matrix, projection, uniforms, rl, |_| true)
+}
+
+/// Dibuja la mitad de los anillos que queda detrás del centro del planeta
+/// (`far_side`) o delante de él. Sin Z-buffer, el orden lejanos -> planeta ->
+/// cercanos basta para que el planeta tape y sea tapado correctamente
+fn render_rings(
+    planet: &Planet,
+    graph: &SceneGraph,
+    projection: &ScreenProjection,
+    uniforms: &ShaderUniforms,
+    rl: &mut RaylibDrawHandle,
+    far_side: bool,
 ) {
-    let view_matrix = camera.get_transform_matrix();
-    let proj_matrix = transform::build_perspective_projection(
-        45.0_f32.to_radians(),
-        width as f32 / height as f32,
-        0.1,
-        100.0,
-    );
-    let viewport_matrix = transform::build_viewport_transform(0.0, 0.0, width as f32, height as f32);
+    let (Some(rings), Some(mesh)) = (planet.shader.rings(), planet.ring_mesh.as_ref()) else {
+        return;
+    };
+    
+    let center_distance = uniforms.camera_position.length();
+    let model_matrix = graph.matrix_relative_to(planet.nodes.rings, planet.nodes.body);
+    render_mesh(mesh, rings, &model_matrix, projection, uniforms, rl, |centroid| {
+        ((centroid - uniforms.camera_position).length() > center_distance) == far_side
+    });
+}
+
+/// Dibuja las capas del planeta (nubes, aurora, atmósfera...) sobre la superficie
+/// ya dibujada, en orden. Solo se ven sus caras delanteras, que cubren el disco
+/// y el borde alrededor. Devuelve el bloom de las capas emisivas
+fn render_layers(
+    planet: &Planet,
+    projection: &ScreenProjection,
+    uniforms: &ShaderUniforms,
+    rl: &mut RaylibDrawHandle,
+) -> Vec<BloomSprite> {
+    let mut bloom = Vec::new();
+    for (index, layer) in planet.shader.layers().iter().enumerate() {
+        let model_matrix = planet.layer_model_matrix(index);
+        bloom.extend(render_mesh(&planet.mesh, layer.shader.as_ref(), &model_matrix, projection, uniforms, rl, |_| true));
+    }
+    bloom
+}
+
+/// Superficie del planeta oscurecida por las sombras de sus capas: el rayo
+/// hacia la luz se corta con la esfera de cada capa y se consulta su opacidad
+struct LayerShadowedSurface<'a> {
+    planet: &'a Planet,
+    /// Paso del espacio del planeta al de cada capa
+    layer_inverses: Vec<Transform4x4>,
+}
+
+impl LayerShadowedSurface<'_> {
+    /// Fracción de la luz que llega a `world_position` tras atravesar las capas
+    fn light_reaching(&self, world_position: Vector3, uniforms: &ShaderUniforms) -> f32 {
+        let light_dir = uniforms.light_direction.normalize();
+        let mut light = 1.0;
+        
+        for (layer, inverse) in self.planet.shader.layers().iter().zip(&self.layer_inverses) {
+            // Salida del rayo por la esfera de la capa (el punto está dentro de ella)
+            let radius = PLANET_RADIUS * layer.radius_scale;
+            let b = world_position.dot(&light_dir);
+            let c = world_position.dot(&world_position) - radius * radius;
+            let discriminant = b * b - c;
+            if discriminant < 0.0 {
+                continue;
+            }
+            let exit = -b + discriminant.sqrt();
+            if exit <= 0.0 {
+                continue;
+            }
+            
+            let hit = world_position + light_dir * exit;
+            let object_hit = inverse.apply_to_vector(&hit);
+            light *= 1.0 - layer.shader.shadow_opacity(object_hit, uniforms);
+        }
+        
+        light
+    }
+}
+
+impl PlanetShader for LayerShadowedSurface<'_> {
+    fn vertex_shader(&self, position: Vector3, normal: Vector3, uv: (f32, f32), uniforms: &ShaderUniforms) -> (Vector3, Vector3) {
+        self.planet.shader.vertex_shader(position, normal, uv, uniforms)
+    }
     
-    let rotation_matrix = transform::build_y_axis_rotation(planet.rotation);
+    fn fragment_shader(&self, position: Vector3, normal: Vector3, uv: (f32, f32), uniforms: &ShaderUniforms) -> ShaderColor {
+        self.shade(position, normal, uv, uniforms).0
+    }
+    
+    fn shade(&self, position: Vector3, normal: Vector3, uv: (f32, f32), uniforms: &ShaderUniforms) -> (ShaderColor, ShaderColor) {
+        let (color, emission) = self.planet.shader.shade(position, normal, uv, uniforms);
+        let light = self.light_reaching(position, uniforms);
+        if light >= 1.0 {
+            return (color, emission);
+        }
+
+        // Las capas tapan el sol, no la luz que emite la propia superficie
+        let shade = |channel: f32, emitted: f32| {
+            let emitted = emitted.min(channel);
+            (channel - emitted) * light + emitted
+        };
+        let shaded = ShaderColor::new(
+            shade(color.r, emission.r),
+            shade(color.g, emission.g),
+            shade(color.b, emission.b),
+            color.a,
+        );
+        (shaded, emission)
+    }
+    
+    fn emission(&self, position: Vector3, normal: Vector3, uniforms: &ShaderUniforms) -> ShaderColor {
+        self.planet.shader.emission(position, normal, uniforms)
+    }
+}
+
+/// Dibuja una malla con su shader y devuelve los triángulos emisivos visibles
+/// para el bloom. Solo se dibujan los triángulos cuyo centro (en el mundo)
+/// acepta `include`
+fn render_mesh(
+    mesh: &Mesh,
+    shader: &dyn PlanetShader,
+    model_matrix: &Transform4x4,
+    projection: &ScreenProjection,
+    uniforms: &ShaderUniforms,
+    rl: &mut RaylibDrawHandle,
+    include: impl Fn(Vector3) -> bool,
+) -> Vec<BloomSprite> {
+    // Los shaders reciben la inversa del modelo para muestrear en espacio de objeto
+    // y el ángulo de un píxel para filtrar el ruido según la distancia
+    let uniforms = ShaderUniforms {
+        world_to_object: model_matrix.inverse(),
+        pixel_angle: projection.vertical_fov / projection.height as f32,
+        ..*uniforms
+    };
+    
+    let mut bloom = Vec::new();
     
     // Renderizar triángulos
-    for i in (0..planet.mesh.indices.len()).step_by(3) {
-        let i1 = planet.mesh.indices[i] as usize;
-        let i2 = planet.mesh.indices[i + 1] as usize;
-        let i3 = planet.mesh.indices[i + 2] as usize;
+    for i in (0..mesh.indices.len()).step_by(3) {
+        let i1 = mesh.indices[i] as usize;
+        let i2 = mesh.indices[i + 1] as usize;
+        let i3 = mesh.indices[i + 2] as usize;
         
-        if i1 >= planet.mesh.vertices.len() || i2 >= planet.mesh.vertices.len() || i3 >= planet.mesh.vertices.len() {
+        if i1 >= mesh.vertices.len() || i2 >= mesh.vertices.len() || i3 >= mesh.vertices.len() {
             continue;
         }
         
-        let v1 = &planet.mesh.vertices[i1];
-        let v2 = &planet.mesh.vertices[i2];
-        let v3 = &planet.mesh.vertices[i3];
+        let v1 = &mesh.vertices[i1];
+        let v2 = &mesh.vertices[i2];
+        let v3 = &mesh.vertices[i3];
         
-        // Aplicar rotación
-        let pos1 = rotation_matrix.apply_to_vector(&v1.position);
-        let pos2 = rotation_matrix.apply_to_vector(&v2.position);
-        let pos3 = rotation_matrix.apply_to_vector(&v3.position);
+        // Aplicar VERTEX SHADER en espacio de objeto: la deformación gira con el planeta
+        let (pos1, norm1) = shader.vertex_shader(v1.position, v1.normal, v1.uv, &uniforms);
+        let (pos2, norm2) = shader.vertex_shader(v2.position, v2.normal, v2.uv, &uniforms);
+        let (pos3, norm3) = shader.vertex_shader(v3.position, v3.normal, v3.uv, &uniforms);
         
-        let norm1 = rotation_matrix.apply_to_vector(&v1.normal);
-        let norm2 = rotation_matrix.apply_to_vector(&v2.normal);
-        let norm3 = rotation_matrix.apply_to_vector(&v3.normal);
+        // Aplicar rotación (espacio de objeto -> mundo)
+        let pos1 = model_matrix.apply_to_vector(&pos1);
+        let pos2 = model_matrix.apply_to_vector(&pos2);
+        let pos3 = model_matrix.apply_to_vector(&pos3);
         
-        // Aplicar VERTEX SHADER para deformación procedural
-        let (pos1, norm1) = planet.shader.vertex_shader(pos1, norm1, v1.uv, uniforms);
-        let (pos2, norm2) = planet.shader.vertex_shader(pos2, norm2, v2.uv, uniforms);
-        let (pos3, norm3) = planet.shader.vertex_shader(pos3, norm3, v3.uv, uniforms);
+        let norm1 = model_matrix.apply_to_direction(&norm1).normalize();
+        let norm2 = model_matrix.apply_to_direction(&norm2).normalize();
+        let norm3 = model_matrix.apply_to_direction(&norm3).normalize();
+        
+        let centroid = (pos1 + pos2 + pos3) * (1.0 / 3.0);
+        if !include(centroid) {
+            continue;
+        }
         
         // Transformar a espacio de pantalla
-        let screen1 = viewport_matrix.apply_to_vector(&proj_matrix.apply_to_vector(&view_matrix.apply_to_vector(&pos1)));
-        let screen2 = viewport_matrix.apply_to_vector(&proj_matrix.apply_to_vector(&view_matrix.apply_to_vector(&pos2)));
-        let screen3 = viewport_matrix.apply_to_vector(&proj_matrix.apply_to_vector(&view_matrix.apply_to_vector(&pos3)));
+        let screen1 = projection.project(&pos1);
+        let screen2 = projection.project(&pos2);
+        let screen3 = projection.project(&pos3);
         
-        // Calcular colores usando fragment shader
-        let color1 = planet.shader.fragment_shader(pos1, norm1, v1.uv, uniforms);
-        let color2 = planet.shader.fragment_shader(pos2, norm2, v2.uv, uniforms);
-        let color3 = planet.shader.fragment_shader(pos3, norm3, v3.uv, uniforms);
+        // Color y luz propia de cada vértice en una sola pasada del shader
+        let (color1, emission1) = shader.shade(pos1, norm1, v1.uv, &uniforms);
+        let (color2, emission2) = shader.shade(pos2, norm2, v2.uv, &uniforms);
+        let (color3, emission3) = shader.shade(pos3, norm3, v3.uv, &uniforms);
         
         // Dibujar triángulo (simplificado - usar color promedio)
         let avg_color = ShaderColor::new(
@@ -121,10 +725,16 @@ fn render_planet_software(
             (color1.a + color2.a + color3.a) / 3.0,
         );
         
+        // Luz propia del triángulo (independiente de la iluminación) para el bloom
+        let avg_emission = ShaderColor::new(
+            (emission1.r + emission2.r + emission3.r) / 3.0,
+            (emission1.g + emission2.g + emission3.g) / 3.0,
+            (emission1.b + emission2.b + emission3.b) / 3.0,
+            1.0,
+        );
+        
         // Verificar que los puntos estén en pantalla
-        if screen1.x >= 0.0 && screen1.x < width as f32 && screen1.y >= 0.0 && screen1.y < height as f32 &&
-           screen2.x >= 0.0 && screen2.x < width as f32 && screen2.y >= 0.0 && screen2.y < height as f32 &&
-           screen3.x >= 0.0 && screen3.x < width as f32 && screen3.y >= 0.0 && screen3.y < height as f32 {
+        if projection.is_on_screen(&screen1) && projection.is_on_screen(&screen2) && projection.is_on_screen(&screen3) {
             
             rl.draw_triangle(
                 Vector2::new(screen1.x, screen1.y),
@@ -132,6 +742,110 @@ fn render_planet_software(
                 Vector2::new(screen3.x, screen3.y),
                 avg_color.to_raylib_color(),
             );
+            
+            // Solo las caras que miran a la cámara aportan bloom
+            let facing = (norm1 + norm2 + norm3).dot(&(uniforms.camera_position - centroid)) > 0.0;
+            let strength = avg_emission.r.max(avg_emission.g).max(avg_emission.b);
+            
+            if facing && strength > BLOOM_THRESHOLD {
+                let center = Vector2::new(
+                    (screen1.x + screen2.x + screen3.x) / 3.0,
+                    (screen1.y + screen2.y + screen3.y) / 3.0,
+                );
+                let extent = [screen1, screen2, screen3].iter()
+                    .map(|s| ((s.x - center.x).powi(2) + (s.y - center.y).powi(2)).sqrt())
+                    .fold(0.0, f32::max);
+                
+                bloom.push(BloomSprite {
+                    center,
+                    radius: extent * 3.0,
+                    color: avg_emission,
+                    strength,
+                });
+            }
+        }
+    }
+    
+    bloom
+}
+
+/// Bloom aproximado: cada triángulo emisivo se derrama en un degradado radial
+/// translúcido sobre lo ya dibujado
+fn render_bloom(sprites: &[BloomSprite], rl: &mut RaylibDrawHandle) {
+    for sprite in sprites {
+        // El tono sale de la emisión normalizada; la intensidad va al alfa
+        let hue = ShaderColor::new(
+            sprite.color.r / sprite.strength,
+            sprite.color.g / sprite.strength,
+            sprite.color.b / sprite.strength,
+            1.0,
+        );
+        let opacity = ((sprite.strength - BLOOM_THRESHOLD) * 0.35).min(0.5);
+        let inner = ShaderColor::new(hue.r, hue.g, hue.b, opacity);
+        let outer = ShaderColor::new(hue.r, hue.g, hue.b, 0.0);
+        
+        rl.draw_circle_gradient(
+            sprite.center.x as i32,
+            sprite.center.y as i32,
+            sprite.radius,
+            inner.to_raylib_color(),
+            outer.to_raylib_color(),
+        );
+    }
+}
+
+/// Dibuja el halo del planeta como anillos concéntricos orientados a la cámara.
+/// Cada anillo se divide en segmentos cuyo alcance varía con ruido animado
+fn render_halo(
+    planet: &Planet,
+    halo: &HaloStyle,
+    projection: &ScreenProjection,
+    time: f32,
+    rl: &mut RaylibDrawHandle,
+) {
+    const SEGMENTS: usize = 72;
+    const RINGS: usize = 8;
+    
+    // Centro y radio del disco en pantalla (el eje derecho de la cámara es la primera
+    // fila de la vista, escalada por el marco del cuerpo)
+    let view = &projection.view_matrix.elements;
+    let camera_right = Vector3::new(view[0][0], view[0][1], view[0][2]).normalize();
+    let center = projection.project(&Vector3::new(0.0, 0.0, 0.0));
+    let edge = projection.project(&(camera_right * planet.mesh.bounding_radius()));
+    let disk_radius = ((edge.x - center.x).powi(2) + (edge.y - center.y).powi(2)).sqrt();
+    
+    // Alcance del halo en cada ángulo; el ruido se muestrea sobre un círculo para cerrar sin costura
+    let noise = NoiseSource::new(0);
+    let reach = |angle: f32| {
+        let wobble = noise.simplex3(angle.cos() * 2.0, angle.sin() * 2.0, time * 0.2);
+        1.0 + (halo.extent - 1.0) * (1.0 + halo.streamers * wobble).max(0.0)
+    };
+    let point = |angle: f32, radius: f32| {
+        Vector2::new(center.x + angle.cos() * radius, center.y + angle.sin() * radius)
+    };
+    
+    for segment in 0..SEGMENTS {
+        let angle0 = segment as f32 / SEGMENTS as f32 * std::f32::consts::TAU;
+        let angle1 = (segment + 1) as f32 / SEGMENTS as f32 * std::f32::consts::TAU;
+        let reach0 = reach(angle0);
+        let reach1 = reach(angle1);
+        
+        for ring in 0..RINGS {
+            let t0 = ring as f32 / RINGS as f32;
+            let t1 = (ring + 1) as f32 / RINGS as f32;
+            // Empieza un poco dentro del disco para tapar el borde poligonal
+            let radius = |t: f32, reach: f32| disk_radius * (0.97 + (reach - 0.97) * t);
+            
+            let fade = (1.0 - t0) * (1.0 - t0);
+            let color = ShaderColor::new(halo.color.r, halo.color.g, halo.color.b, halo.color.a * fade).to_raylib_color();
+            
+            // Orden antihorario en pantalla para que raylib no descarte los triángulos
+            let inner0 = point(angle0, radius(t0, reach0));
+            let inner1 = point(angle1, radius(t0, reach1));
+            let outer0 = point(angle0, radius(t1, reach0));
+            let outer1 = point(angle1, radius(t1, reach1));
+            rl.draw_triangle(inner0, inner1, outer1, color);
+            rl.draw_triangle(inner0, outer1, outer0, color);
         }
     }
 }
@@ -216,63 +930,227 @@ fn main() {
         .build();
 
     let mut camera = OrbitalCamera::new();
-    let mut planets = vec![
-        Planet::new(PlanetType::Rocky),
-        Planet::new(PlanetType::GasGiant),
-        Planet::new(PlanetType::Crystal),
-        Planet::new(PlanetType::Nebula),
-        Planet::new(PlanetType::Saturn),
-    ];
+    let registry = planets::builtin_registry();
+    let mut graph = SceneGraph::new();
+    let mut planets: Vec<Planet> = registry.iter().map(|descriptor| Planet::new(descriptor, &mut graph)).collect();
+    
+    // Vista de sistema: la estrella ilumina al resto desde el centro
+    let star = registry.index_of("star");
+    for (index, placement) in solar_system::layout(&registry).iter().enumerate() {
+        let parent_orbit = placement.orbit.map(|(parent, _)| planets[parent].nodes.orbit);
+        planets[index]
+            .set_placement(placement, parent_orbit, &mut graph)
+            .expect("ERROR: jerarquía de órbitas inválida en el sistema integrado");
+    }
+    for planet in &planets {
+        planet.sync_nodes(&mut graph);
+    }
+    graph.update();
+    // Nodo que sigue la cámara en la vista de sistema
+    let mut focused: Option<NodeId> = None;
+    // Gravedad de N cuerpos en lugar de órbitas fijas (solo en la vista de sistema)
+    let mut gravity: Option<GravitySimulation> = None;
+    let mut integrator = Integrator::VelocityVerlet;
+    // Reloj del que dependen todos los cuerpos y los shaders
+    let mut clock = SimulationClock::new();
+    // Instante que se está tecleando para saltar a él (tecla T)
+    let mut jump_entry: Option<String> = None;
+    let mut scene_mode = false;
+    let mut show_axis = false;
     
     let mut current_planet = 0;
-    let mut time = 0.0f32;
+    let mut current_page = 0;
+    let page_count = if registry.is_empty() { 1 } else { registry.len().div_ceil(PLANETS_PER_PAGE) };
+    // El fondo titila en tiempo real, al margen del reloj de simulación
+    let mut real_time = 0.0f32;
 
     rl.set_target_fps(60);
 
     while !rl.window_should_close() {
         let dt = rl.get_frame_time();
-        time += dt;
+        real_time += dt;
         
         // Actualizar cámara
         camera.process_input(&rl);
         
-        // Cambiar planeta con teclas
-        if rl.is_key_pressed(KeyboardKey::KEY_ONE) {
-            current_planet = 0;
-        } else if rl.is_key_pressed(KeyboardKey::KEY_TWO) {
-            current_planet = 1;
-        } else if rl.is_key_pressed(KeyboardKey::KEY_THREE) {
-            current_planet = 2;
-        } else if rl.is_key_pressed(KeyboardKey::KEY_FOUR) {
-            current_planet = 3;
-        } else if rl.is_key_pressed(KeyboardKey::KEY_FIVE) {
-            current_planet = 4;
+        // Mientras se teclea el instante de un salto, el teclado es solo para eso
+        let mut jumped = match &mut jump_entry {
+            Some(entry) => read_jump_entry(&mut rl, entry, &mut clock),
+            None => false,
+        };
+        if jumped || (jump_entry.is_some() && rl.is_key_pressed(KeyboardKey::KEY_T)) {
+            jump_entry = None;
+        } else if rl.is_key_pressed(KeyboardKey::KEY_T) {
+            // Descartar lo tecleado antes, incluida la propia T
+            while rl.get_char_pressed().is_some() {}
+            jump_entry = Some(String::new());
+        }
+        let typing = jump_entry.is_some();
+        
+        // Alternar entre un planeta aislado y el sistema completo
+        if !typing && rl.is_key_pressed(KeyboardKey::KEY_TAB) {
+            scene_mode = !scene_mode;
+            focused = None;
+            gravity = None;
+            if scene_mode {
+                // Encuadrar el apoapsis más lejano
+                let extent = planets.iter()
+                    .filter_map(|planet| planet.orbit.map(|orbit| orbit.semi_major_axis * (1.0 + orbit.eccentricity)))
+                    .fold(FOCUS_DISTANCE, f32::max);
+                camera.focus(Vector3::new(0.0, 0.0, 0.0), extent * 1.6, 0.0);
+            } else {
+                camera.focus(Vector3::new(0.0, 0.0, 0.0), FOCUS_DISTANCE, 0.0);
+            }
+        }
+        
+        // Cambiar de página con RePág / AvPág
+        if !typing && rl.is_key_pressed(KeyboardKey::KEY_PAGE_DOWN) {
+            current_page = (current_page + 1) % page_count;
+        } else if !typing && rl.is_key_pressed(KeyboardKey::KEY_PAGE_UP) {
+            current_page = (current_page + page_count - 1) % page_count;
+        }
+        
+        // Teclas 1-9 dentro de la página actual: cambian el planeta o, en la
+        // vista de sistema, llevan la cámara hasta él
+        if let Some(slot) = pressed_number_key(&rl).filter(|_| !typing) {
+            let index = current_page * PLANETS_PER_PAGE + slot;
+            if index < planets.len() {
+                current_planet = index;
+                if scene_mode {
+                    let planet = &planets[index];
+                    camera.focus(graph.world_position(planet.nodes.body), FOCUS_DISTANCE * planet.scale, planet.near_clearance());
+                    focused = Some(planet.nodes.body);
+                }
+            }
+        }
+        
+        // Mostrar u ocultar los ejes de giro
+        if !typing && rl.is_key_pressed(KeyboardKey::KEY_X) {
+            show_axis = !show_axis;
+        }
+        
+        // Nueva variante determinista del planeta actual
+        if !typing && rl.is_key_pressed(KeyboardKey::KEY_R) {
+            planets[current_planet].randomize_seed();
         }
         
-        // Actualizar planeta actual
-        planets[current_planet].update(dt);
+        // Guardar las paletas del planeta actual para editarlas
+        if !typing && rl.is_key_pressed(KeyboardKey::KEY_P) {
+            let planet = &mut planets[current_planet];
+            match export_palettes(planet.id, planet.shader.as_mut()) {
+                Ok(count) => println!("{} paleta(s) de '{}' guardadas en {}/", count, planet.id, PALETTE_DIR),
+                Err(e) => eprintln!("{}", e),
+            }
+        }
+        
+        // Controles del reloj: pausa, velocidad, sentido, paso a paso y vuelta a t = 0
+        if !typing {
+            if rl.is_key_pressed(KeyboardKey::KEY_SPACE) {
+                clock.toggle_pause();
+            }
+            if rl.is_key_pressed(KeyboardKey::KEY_EQUAL) || rl.is_key_pressed(KeyboardKey::KEY_KP_ADD) {
+                clock.faster();
+            } else if rl.is_key_pressed(KeyboardKey::KEY_MINUS) || rl.is_key_pressed(KeyboardKey::KEY_KP_SUBTRACT) {
+                clock.slower();
+            }
+            if rl.is_key_pressed(KeyboardKey::KEY_B) {
+                clock.toggle_reverse();
+            }
+            if rl.is_key_pressed(KeyboardKey::KEY_PERIOD) {
+                clock.step_frame();
+            }
+        }
+        if !typing && rl.is_key_pressed(KeyboardKey::KEY_HOME) {
+            clock.jump_to(0.0);
+            jumped = true;
+        }
+        clock.limit_speed(gravity.as_ref().map(|_| GRAVITY_MAX_SPEED));
+        let simulation_dt = clock.tick(dt);
+        
+        // Alternar entre órbitas de Kepler y gravedad de N cuerpos, y elegir integrador
+        if !typing && scene_mode && rl.is_key_pressed(KeyboardKey::KEY_G) {
+            gravity = match gravity {
+                Some(_) => None,
+                None => Some(start_gravity(&planets, &graph, integrator)),
+            };
+        }
+        if !typing && rl.is_key_pressed(KeyboardKey::KEY_I) {
+            integrator = integrator.next();
+            if let Some(simulation) = &mut gravity {
+                simulation.integrator = integrator;
+            }
+        }
+        
+        // Todos los cuerpos siguen al mismo reloj, se vean o no
+        for planet in &mut planets {
+            planet.set_time(clock.time());
+        }
+        
+        // Volcar el estado en el grafo de escena y resolver las matrices de mundo una vez
+        for planet in &planets {
+            planet.sync_nodes(&mut graph);
+        }
+        // La gravedad no se puede evaluar en un instante arbitrario: tras un
+        // salto vuelve a partir de las órbitas de Kepler en el nuevo tiempo
+        if jumped {
+            if let Some(simulation) = &gravity {
+                graph.update();
+                gravity = Some(start_gravity(&planets, &graph, simulation.integrator));
+            }
+        }
+        if let Some(simulation) = &mut gravity {
+            // La gravedad da posiciones absolutas: cada nodo de órbita guarda la
+            // posición relativa a su cuerpo central
+            simulation.advance(simulation_dt);
+            for (index, planet) in planets.iter().enumerate() {
+                let parent_position = planet.parent.map_or(Vector3::new(0.0, 0.0, 0.0), |parent| simulation.position(parent));
+                graph.node_mut(planet.nodes.orbit).translation = simulation.position(index) - parent_position;
+            }
+        }
+        graph.update();
+        
+        // La cámara acompaña al cuerpo enfocado a lo largo de su órbita
+        if let Some(node) = focused {
+            camera.follow(graph.world_position(node));
+        }
         
         // Configurar uniforms para shaders
         let uniforms = ShaderUniforms {
-            time,
+            time: clock.shader_time(),
             light_direction: Vector3::new(1.0, 1.0, 1.0).normalize(),
             camera_position: camera.position,
+            world_to_object: Transform4x4::identity(),
+            pixel_angle: 0.0,
         };
         
+        let projection = ScreenProjection::new(&camera, 1024, 768);
+        
         let mut d = rl.begin_drawing(&thread);
         
-        render_galaxy_background(&mut d, 1024, 768, time);
+        render_galaxy_background(&mut d, 1024, 768, real_time);
         
-        render_planet_software(
-            &planets[current_planet],
-            &camera,
-            &uniforms,
-            &mut d,
-            1024,
-            768,
-        );
+        if scene_mode {
+            render_scene(&planets, &graph, star, &projection, &uniforms, show_axis, &mut d);
+        } else {
+            // Vista individual: el planeta actual en el origen, a escala 1
+            render_body(&planets[current_planet], &graph, &Transform4x4::identity(), &projection, &uniforms, show_axis, &mut d);
+        }
         
         let current_fps = d.get_fps() as i32;
-        render_ui(&mut d, current_planet, current_fps);
+        let status = SimulationStatus {
+            scene_mode,
+            time: clock.time(),
+            speed: clock.speed(),
+            paused: clock.is_paused(),
+            reversed: clock.is_reversed(),
+            jump_entry: jump_entry.clone(),
+            gravity: gravity.as_ref().map(|simulation| GravityReadout {
+                integrator: simulation.integrator.name(),
+                energy_drift: simulation.energy_drift(),
+                momentum_drift: simulation.momentum_drift(),
+                lag: simulation.lag(),
+            }),
+        };
+        render_ui(&mut d, &registry, current_planet, current_page, &planets[current_planet].params, &status, current_fps);
     }
 }
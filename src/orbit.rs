@@ -0,0 +1,196 @@
+// ============================================================================
+// ÓRBITAS KEPLERIANAS
+// Cada órbita se describe con los seis elementos clásicos respecto a su cuerpo
+// central. Resolviendo la ecuación de Kepler se obtiene la posición (y la
+// velocidad) en cualquier instante de la simulación, sin acumular error.
+// El tiempo llega en `f64`: con `f32` la posición avanzaría a saltos en
+// instantes grandes
+// El plano de referencia es XZ (la eclíptica) con el norte hacia +Y
+// ============================================================================
+
+use crate::vector::Vector3;
+use std::f32::consts::{PI, TAU};
+
+/// Excentricidad máxima admitida: solo órbitas cerradas (elipses)
+const MAX_ECCENTRICITY: f32 = 0.99;
+
+/// Elementos orbitales clásicos más el parámetro gravitacional (G·M) del
+/// cuerpo central, que fija el periodo por la tercera ley de Kepler
+#[derive(Debug, Clone, Copy)]
+pub struct OrbitalElements {
+    /// Semieje mayor (a), en unidades del mundo
+    pub semi_major_axis: f32,
+    /// Excentricidad (e) en [0, 1)
+    pub eccentricity: f32,
+    /// Inclinación (i) respecto a la eclíptica, en radianes
+    pub inclination: f32,
+    /// Longitud del nodo ascendente (Ω), en radianes
+    pub longitude_of_ascending_node: f32,
+    /// Argumento del periapsis (ω), en radianes
+    pub argument_of_periapsis: f32,
+    /// Anomalía media en la época (M0, tiempo 0), en radianes
+    pub mean_anomaly_at_epoch: f32,
+    /// G·M del cuerpo central
+    pub gravitational_parameter: f32,
+}
+
+impl OrbitalElements {
+    pub fn new(
+        semi_major_axis: f32,
+        eccentricity: f32,
+        inclination: f32,
+        longitude_of_ascending_node: f32,
+        argument_of_periapsis: f32,
+        mean_anomaly_at_epoch: f32,
+        gravitational_parameter: f32,
+    ) -> Self {
+        OrbitalElements {
+            semi_major_axis: semi_major_axis.max(0.001),
+            eccentricity: eccentricity.clamp(0.0, MAX_ECCENTRICITY),
+            inclination,
+            longitude_of_ascending_node,
+            argument_of_periapsis,
+            mean_anomaly_at_epoch,
+            gravitational_parameter: gravitational_parameter.max(0.0001),
+        }
+    }
+
+    /// Movimiento medio n = sqrt(G·M / a³), en radianes por unidad de tiempo
+    pub fn mean_motion(&self) -> f32 {
+        (self.gravitational_parameter / self.semi_major_axis.powi(3)).sqrt()
+    }
+
+    /// Anomalía excéntrica en el instante `time`. La anomalía media se reduce
+    /// a una vuelta en `f64` antes de pasar a `f32`
+    fn eccentric_anomaly(&self, time: f64) -> f32 {
+        let mean_anomaly = (self.mean_anomaly_at_epoch as f64 + self.mean_motion() as f64 * time)
+            .rem_euclid(std::f64::consts::TAU);
+        solve_kepler(mean_anomaly as f32, self.eccentricity)
+    }
+
+    /// Posición relativa al cuerpo central en el instante `time`
+    pub fn position_at(&self, time: f64) -> Vector3 {
+        let anomaly = self.eccentric_anomaly(time);
+        let e = self.eccentricity;
+        let a = self.semi_major_axis;
+
+        // Coordenadas en el plano de la órbita, con el periapsis sobre el eje x
+        let x = a * (anomaly.cos() - e);
+        let y = a * (1.0 - e * e).sqrt() * anomaly.sin();
+        self.orbital_plane_to_world(x, y)
+    }
+
+    /// Velocidad relativa al cuerpo central en el instante `time`
+    pub fn velocity_at(&self, time: f64) -> Vector3 {
+        let anomaly = self.eccentric_anomaly(time);
+        let e = self.eccentricity;
+        let a = self.semi_major_axis;
+
+        // Derivada de la anomalía excéntrica respecto al tiempo
+        let rate = self.mean_motion() / (1.0 - e * anomaly.cos());
+        let x = -a * anomaly.sin() * rate;
+        let y = a * (1.0 - e * e).sqrt() * anomaly.cos() * rate;
+        self.orbital_plane_to_world(x, y)
+    }
+
+    /// Gira un vector del plano de la órbita (ω, i, Ω) y lo pasa a los ejes del
+    /// mundo: el polo de la eclíptica es +Y y las órbitas directas giran en
+    /// sentido antihorario vistas desde arriba
+    fn orbital_plane_to_world(&self, x: f32, y: f32) -> Vector3 {
+        let (sin_node, cos_node) = self.longitude_of_ascending_node.sin_cos();
+        let (sin_peri, cos_peri) = self.argument_of_periapsis.sin_cos();
+        let (sin_incl, cos_incl) = self.inclination.sin_cos();
+
+        let ecliptic_x = x * (cos_node * cos_peri - sin_node * sin_peri * cos_incl)
+            - y * (cos_node * sin_peri + sin_node * cos_peri * cos_incl);
+        let ecliptic_y = x * (sin_node * cos_peri + cos_node * sin_peri * cos_incl)
+            + y * (cos_node * cos_peri * cos_incl - sin_node * sin_peri);
+        let ecliptic_z = x * sin_peri * sin_incl + y * cos_peri * sin_incl;
+
+        Vector3::new(ecliptic_x, ecliptic_z, -ecliptic_y)
+    }
+}
+
+/// Resuelve la ecuación de Kepler E - e·sin(E) = M por Newton-Raphson
+pub fn solve_kepler(mean_anomaly: f32, eccentricity: f32) -> f32 {
+    // Reducir M a [-π, π) para que la iteración converja rápido
+    let mean_anomaly = (mean_anomaly + PI).rem_euclid(TAU) - PI;
+    // Con excentricidades altas, empezar en π evita que Newton se salga
+    let mut anomaly = if eccentricity < 0.8 { mean_anomaly } else { PI.copysign(mean_anomaly) };
+
+    for _ in 0..12 {
+        let error = anomaly - eccentricity * anomaly.sin() - mean_anomaly;
+        let step = error / (1.0 - eccentricity * anomaly.cos());
+        anomaly -= step;
+        if step.abs() < 1e-6 {
+            break;
+        }
+    }
+    anomaly
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Órbita con el periapsis en t = 0 y ángulos cualesquiera
+    fn orbit(eccentricity: f32) -> OrbitalElements {
+        OrbitalElements::new(4.0, eccentricity, 0.3, 1.1, -0.7, 0.0, 50.0)
+    }
+
+    #[test]
+    fn kepler_converges_up_to_high_eccentricity() {
+        for &eccentricity in &[0.0, 0.3, 0.7, 0.9, 0.99] {
+            for step in 0..64 {
+                let mean_anomaly = -PI + step as f32 * (TAU / 64.0);
+                let anomaly = solve_kepler(mean_anomaly, eccentricity);
+                let residual = anomaly - eccentricity * anomaly.sin() - mean_anomaly;
+                assert!(residual.abs() < 1e-4, "e = {}, M = {}: residuo {}", eccentricity, mean_anomaly, residual);
+            }
+        }
+    }
+
+    #[test]
+    fn kepler_wraps_the_mean_anomaly() {
+        for &mean_anomaly in &[0.4, 2.9, -1.3] {
+            let reference = solve_kepler(mean_anomaly, 0.5);
+            for turns in [-3.0, 1.0, 10.0] {
+                let wrapped = solve_kepler(mean_anomaly + turns * TAU, 0.5);
+                assert!((wrapped.sin() - reference.sin()).abs() < 1e-3);
+                assert!((wrapped.cos() - reference.cos()).abs() < 1e-3);
+            }
+        }
+    }
+
+    #[test]
+    fn periapsis_and_apoapsis_distances() {
+        let elements = orbit(0.3);
+        let half_period = std::f64::consts::PI / elements.mean_motion() as f64;
+
+        let periapsis = elements.position_at(0.0).length();
+        let apoapsis = elements.position_at(half_period).length();
+        assert!((periapsis - 4.0 * 0.7).abs() < 1e-3, "periapsis {}", periapsis);
+        assert!((apoapsis - 4.0 * 1.3).abs() < 1e-3, "apoapsis {}", apoapsis);
+    }
+
+    #[test]
+    fn position_is_periodic_at_large_times() {
+        let elements = orbit(0.2);
+        let period = std::f64::consts::TAU / elements.mean_motion() as f64;
+        let start = elements.position_at(0.25);
+        let later = elements.position_at(0.25 + period * 100_000.0);
+        assert!((later - start).length() < 1e-3);
+    }
+
+    #[test]
+    fn velocity_matches_finite_difference() {
+        let elements = orbit(0.6);
+        let step = 1e-3;
+        for &time in &[0.0, 0.37, 1.9, 5.2] {
+            let numeric = (elements.position_at(time + step) - elements.position_at(time - step)) * (1.0 / (2.0 * step) as f32);
+            let analytic = elements.velocity_at(time);
+            assert!((numeric - analytic).length() < 1e-2 * analytic.length().max(1.0),
+                "t = {}: {:?} != {:?}", time, numeric, analytic);
+        }
+    }
+}
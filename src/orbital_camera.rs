@@ -20,6 +20,8 @@ pub struct OrbitalCamera {
     pub azimuth: f32,           
     pub elevation: f32,         
     pub radius: f32,            
+    pub min_radius: f32,
+    pub max_radius: f32,
 
     // Configuración de sensibilidad
     pub orbit_sensitivity: f32,  
@@ -37,6 +39,8 @@ impl OrbitalCamera {
             azimuth: 0.0,
             elevation: 0.0,
             radius: 5.0,
+            min_radius: 1.0,
+            max_radius: 20.0,
             orbit_sensitivity: 2.0,
             zoom_sensitivity: 1.0,
             pan_sensitivity: 0.5,
@@ -64,7 +68,7 @@ impl OrbitalCamera {
         // Control de zoom con rueda del mouse
         if scroll_delta != 0.0 {
             self.radius -= scroll_delta * self.zoom_sensitivity;
-            self.radius = self.radius.clamp(1.0, 20.0);
+            self.radius = self.radius.clamp(self.min_radius, self.max_radius);
         }
 
         // Control de paneo (desplazamiento) con clic derecho
@@ -94,6 +98,22 @@ impl OrbitalCamera {
         self.recalculate_position();
     }
 
+    /// Centra la cámara en `target` a la distancia `radius`, conservando la
+    /// orientación. El rango de zoom se ajusta a esa distancia y el zoom nunca
+    /// se acerca a menos de `clearance` del objetivo
+    pub fn focus(&mut self, target: Vector3, radius: f32, clearance: f32) {
+        self.min_radius = (radius * 0.2).max(clearance);
+        self.radius = radius.max(self.min_radius);
+        self.max_radius = radius * 4.0;
+        self.follow(target);
+    }
+
+    /// Desplaza el punto focal a `target` sin cambiar la distancia ni la orientación
+    pub fn follow(&mut self, target: Vector3) {
+        self.focal_point = target;
+        self.recalculate_position();
+    }
+
     /// Recalcula la posición de la cámara usando coordenadas esféricas
     fn recalculate_position(&mut self) {
         let horizontal_distance = self.radius * self.elevation.cos();
@@ -0,0 +1,120 @@
+// ============================================================================
+// PLANETA 9: ASTEROIDE
+// Características: Cuerpo irregular con malla propia (forma de patata con
+// cráteres), regolito oscuro con rayos de eyección y luz dura de un cuerpo
+// sin atmósfera
+// ============================================================================
+
+use crate::vector::Vector3;
+use crate::sphere::Mesh;
+use crate::asteroid::AsteroidShape;
+use crate::craters::CraterField;
+use crate::shaders::{ShaderColor, ShaderUniforms, ShaderParams, PlanetShader, NoiseSource, NoiseBasis, smoothstep, mix, mix_color};
+use super::registry::PlanetDescriptor;
+
+pub struct AsteroidPlanetShader {
+    noise: NoiseSource,
+    shape: AsteroidShape,
+    craters: CraterField,
+}
+
+impl AsteroidPlanetShader {
+    pub fn new(shape: AsteroidShape) -> Self {
+        AsteroidPlanetShader {
+            noise: NoiseSource::new(shape.seed.wrapping_add(3)),
+            craters: shape.crater_field(),
+            shape,
+        }
+    }
+}
+
+impl PlanetShader for AsteroidPlanetShader {
+    fn vertex_shader(&self, position: Vector3, normal: Vector3, _uv: (f32, f32), _uniforms: &ShaderUniforms) -> (Vector3, Vector3) {
+        // La forma ya viene en la malla
+        (position, normal)
+    }
+
+    fn fragment_shader(&self, position: Vector3, normal: Vector3, _uv: (f32, f32), uniforms: &ShaderUniforms) -> ShaderColor {
+        // === PALETA DE REGOLITO ===
+        let dark_regolith = ShaderColor::from_rgb(52, 48, 44);    // Regolito carbonáceo
+        let dusty = ShaderColor::from_rgb(96, 88, 78);            // Polvo
+        let fresh_rock = ShaderColor::from_rgb(140, 134, 124);    // Roca expuesta
+
+        let object_position = uniforms.object_position(position);
+        let footprint = uniforms.footprint(position);
+
+        // Manchas de albedo del polvo
+        let albedo = self.noise.fbm3d_filtered(
+            NoiseBasis::Value,
+            object_position.x * 9.0,
+            object_position.y * 9.0,
+            object_position.z * 9.0,
+            4,
+            footprint * 9.0,
+        );
+        let base_color = mix_color(dark_regolith, dusty, albedo);
+
+        // En las pendientes fuertes el polvo resbala y asoma roca más clara
+        let object_normal = uniforms.world_to_object.apply_to_direction(&normal).normalize();
+        let slope = 1.0 - object_normal.dot(&object_position.normalize()).max(0.0);
+        let base_color = mix_color(base_color, fresh_rock, smoothstep(0.15, 0.4, slope) * 0.6);
+
+        // Los mismos cráteres de la malla: fondos algo más oscuros y rayos claros
+        // alrededor de los impactos recientes
+        let craters = self.craters.sample(self.shape.surface_direction(object_position), footprint / object_position.length());
+        let base_color = mix_color(base_color, dark_regolith, craters.interior * 0.25);
+        let base_color = mix_color(base_color, fresh_rock, craters.ejecta * 0.7);
+
+        // === ILUMINACIÓN DE UN CUERPO SIN ATMÓSFERA ===
+        // Mezcla de Lambert y Lommel-Seeliger: el regolito no se oscurece hacia
+        // el limbo como una superficie lambertiana
+        let light_dir = uniforms.light_direction.normalize();
+        let view_dir = (uniforms.camera_position - position).normalize();
+        let incidence = normal.dot(&light_dir).max(0.0);
+        let emergence = normal.dot(&view_dir).max(0.0);
+        let lommel_seeliger = if incidence > 0.0 { 2.0 * incidence / (incidence + emergence) } else { 0.0 };
+        let diffuse = mix(incidence, lommel_seeliger, 0.5);
+        let ambient = 0.03;
+        let lighting = ambient + diffuse;
+
+        ShaderColor::new(
+            (base_color.r * lighting).clamp(0.0, 1.0),
+            (base_color.g * lighting).clamp(0.0, 1.0),
+            (base_color.b * lighting).clamp(0.0, 1.0),
+            1.0,
+        )
+    }
+
+    fn mesh(&self) -> Option<Mesh> {
+        Some(self.shape.mesh())
+    }
+}
+
+pub fn descriptor() -> PlanetDescriptor {
+    PlanetDescriptor {
+        id: "asteroid",
+        name: "Asteroide",
+        description: "Cuerpo irregular con cráteres",
+        icon: "🪨",
+        color: ShaderColor::from_rgb(130, 120, 110),
+        rotation_speed: 0.7,
+        scene_scale: 0.35,
+        axial_tilt: 1.1,
+        precession_rate: 0.2,
+        axis_longitude: 0.0,
+        default_params: ShaderParams::new()
+            .with("seed", 9.0)
+            .with("elongation", 0.6)
+            .with("lumpiness", 0.6)
+            .with("crater_count", 40.0),
+        factory: |params| {
+            let shape = AsteroidShape::new(
+                params.seed(),
+                params.get("elongation", 0.6),
+                params.get("lumpiness", 0.6),
+                params.get("crater_count", 40.0).max(0.0) as u32,
+            );
+            Box::new(AsteroidPlanetShader::new(shape))
+        },
+    }
+}
@@ -1,12 +1,143 @@
 // ============================================================================
 // PLANETA 3: PLANETA SCI-FI TECNOLÓGICO
-// Características: Líneas de energía, circuitos, pulsos tecnológicos, hologramas
+// Características: Líneas de energía, circuitos, pulsos tecnológicos, hologramas.
+// Los circuitos brillan con más fuerza en el lado nocturno
 // ============================================================================
 
 use crate::vector::Vector3;
-use crate::shaders::{ShaderColor, ShaderUniforms, PlanetShader, fbm, fbm3d, voronoi_noise, simple_noise, smoothstep, mix_color};
+use crate::shaders::{ShaderColor, ShaderUniforms, ShaderParams, PlanetShader, NoiseSource, NoiseBasis, DistanceMetric, smoothstep, mix, mix_color, spherical_uv, triplanar, night_mask};
+use super::registry::PlanetDescriptor;
 
-pub struct CrystalPlanetShader;
+pub struct CrystalPlanetShader {
+    noise: NoiseSource,
+}
+
+/// Fracción del brillo de los circuitos que se ve a plena luz del día;
+/// de noche brillan con toda su intensidad
+const DAY_CIRCUIT_GLOW: f32 = 0.3;
+
+impl CrystalPlanetShader {
+    pub fn new(seed: u32) -> Self {
+        CrystalPlanetShader {
+            noise: NoiseSource::new(seed),
+        }
+    }
+
+    /// Pulso de energía que recorre el planeta, en [0, 1]
+    fn energy_pulse(&self, position: Vector3, time: f32) -> f32 {
+        let pulse_frequency = 5.0;
+        let pulse_wave = (time * pulse_frequency + position.length() * 3.0).sin() * 0.5 + 0.5;
+        let pulse_wave2 = (time * pulse_frequency * 1.5 - position.length() * 2.0).sin() * 0.5 + 0.5;
+        pulse_wave * 0.6 + pulse_wave2 * 0.4
+    }
+
+    /// Luz propia de los circuitos, flujos de datos, nodos y escaneo, a plena
+    /// intensidad. Se suma sobre la superficie iluminada
+    fn circuit_glow(&self, position: Vector3, uniforms: &ShaderUniforms) -> ShaderColor {
+        // === PALETA DE ENERGÍA ===
+        let tech_blue = ShaderColor::from_rgb(0, 150, 255);        // Azul tecnológico
+        let cyber_cyan = ShaderColor::from_rgb(0, 255, 255);       // Cian cibernético
+        let neon_green = ShaderColor::from_rgb(0, 255, 150);       // Verde neón
+        let hot_pink = ShaderColor::from_rgb(255, 0, 150);         // Rosa caliente
+        let energy_white = ShaderColor::from_rgb(200, 255, 255);   // Blanco energético
+        let warning_orange = ShaderColor::from_rgb(255, 150, 0);   // Naranja advertencia
+
+        let object_position = uniforms.object_position(position);
+        let direction = object_position.normalize();
+        let (longitude, latitude) = spherical_uv(direction);
+        let direction_footprint = uniforms.footprint(position) / object_position.length();
+        let energy_pulse = self.energy_pulse(position, uniforms.time);
+
+        // === CIRCUITOS HEXAGONALES ===
+        // Celdas 3D sobre la superficie: sin estiramiento en los polos. La
+        // métrica Manhattan da bordes rectos, como pistas de circuito
+        let hex_pattern = self.noise.cellular3(
+            object_position.x * 4.0,
+            object_position.y * 4.0,
+            object_position.z * 4.0,
+            DistanceMetric::Manhattan,
+        );
+        let hex_borders = smoothstep(0.08, 0.02, hex_pattern.edge_distance());
+
+        // === FLUJO DE DATOS ===
+        // Proyección triplanar: sin costura ni pellizco en los polos
+        let data_flow1 = triplanar(direction, 15.0, 4.0, |a, b| {
+            self.noise.fbm_filtered(a + uniforms.time * 0.5, b, 4, direction_footprint * 15.0)
+        });
+        let data_flow2 = triplanar(direction, 15.0, 4.0, |a, b| {
+            self.noise.fbm_filtered(a * 1.3 - uniforms.time * 0.7, b * 0.7 + uniforms.time * 0.3, 3, direction_footprint * 15.0 * 1.3)
+        });
+        let data_streams = smoothstep(0.6, 0.8, data_flow1) + smoothstep(0.65, 0.85, data_flow2);
+
+        // === NODOS DE PODER ===
+        let power_nodes = self.noise.cellular3(
+            object_position.x * 3.0,
+            object_position.y * 3.0,
+            object_position.z * 3.0,
+            DistanceMetric::Euclidean,
+        ).f1;
+        let node_centers = smoothstep(0.08, 0.05, power_nodes);
+        let node_glow = smoothstep(0.15, 0.05, power_nodes);
+
+        // === ESCANEO HOLOGRÁFICO ===
+        let scan_line = (latitude * 10.0 - uniforms.time * 2.0) % 1.0;
+        let scan_intensity = smoothstep(0.05, 0.0, (scan_line - 0.5).abs());
+
+        // === INTERFERENCIA DIGITAL ===
+        let glitch = self.noise.simple_noise(
+            (uniforms.time * 10.0).floor() * 0.1,
+            (latitude * 20.0).floor()
+        );
+        let glitch_effect = if glitch > 0.95 {
+            self.noise.simple_noise(longitude * 100.0 + uniforms.time * 50.0, latitude) * 0.3
+        } else {
+            0.0
+        };
+
+        // === CONSTRUCCIÓN DEL BRILLO (sobre negro: sin emisión, no suma nada) ===
+        let mut glow = ShaderColor::BLACK;
+
+        if hex_borders > 0.1 {
+            let circuit_color = mix_color(cyber_cyan, tech_blue, energy_pulse);
+            glow = mix_color(glow, circuit_color, hex_borders * 0.8);
+        }
+
+        if data_streams > 0.5 {
+            let stream_color = mix_color(neon_green, cyber_cyan, (uniforms.time * 2.0).sin() * 0.5 + 0.5);
+            glow = mix_color(glow, stream_color, data_streams * 0.7);
+        }
+
+        if node_centers > 0.5 {
+            let node_pulse = (uniforms.time * 4.0 + longitude * std::f32::consts::TAU * 3.0).sin() * 0.5 + 0.5;
+            let node_color = mix_color(hot_pink, energy_white, node_pulse);
+            glow = mix_color(glow, node_color, node_centers);
+        }
+
+        if node_glow > 0.3 {
+            glow = mix_color(glow, warning_orange, node_glow * 0.4 * energy_pulse);
+        }
+
+        if scan_intensity > 0.1 {
+            glow = mix_color(glow, energy_white, scan_intensity * 0.8);
+        }
+
+        if glitch_effect > 0.1 {
+            let glitch_color = mix_color(hot_pink, cyber_cyan, glitch);
+            glow = mix_color(glow, glitch_color, glitch_effect);
+        }
+
+        glow
+    }
+
+    /// Brillo de los circuitos atenuado en el lado diurno: se intensifica al
+    /// cruzar el terminador hacia la noche
+    fn night_circuit_glow(&self, position: Vector3, normal: Vector3, uniforms: &ShaderUniforms) -> ShaderColor {
+        let night = night_mask(normal, uniforms.light_direction.normalize());
+        let strength = mix(DAY_CIRCUIT_GLOW, 1.0, night);
+        let glow = self.circuit_glow(position, uniforms);
+        ShaderColor::new(glow.r * strength, glow.g * strength, glow.b * strength, 1.0)
+    }
+}
 
 impl PlanetShader for CrystalPlanetShader {
     fn vertex_shader(&self, position: Vector3, normal: Vector3, _uv: (f32, f32), uniforms: &ShaderUniforms) -> (Vector3, Vector3) {
@@ -17,6 +148,11 @@ impl PlanetShader for CrystalPlanetShader {
     }
 
     fn fragment_shader(&self, position: Vector3, normal: Vector3, uv: (f32, f32), uniforms: &ShaderUniforms) -> ShaderColor {
+        self.shade(position, normal, uv, uniforms).0
+    }
+
+    /// El brillo de los circuitos se calcula una vez y sirve de color y de emisión
+    fn shade(&self, position: Vector3, normal: Vector3, _uv: (f32, f32), uniforms: &ShaderUniforms) -> (ShaderColor, ShaderColor) {
         // === PALETA TECNOLÓGICA ===
         let base_dark = ShaderColor::from_rgb(10, 15, 30);         // Base oscura
         let tech_blue = ShaderColor::from_rgb(0, 150, 255);        // Azul tecnológico
@@ -24,61 +160,33 @@ impl PlanetShader for CrystalPlanetShader {
         let neon_green = ShaderColor::from_rgb(0, 255, 150);       // Verde neón
         let electric_purple = ShaderColor::from_rgb(150, 0, 255);  // Púrpura eléctrico
         let hot_pink = ShaderColor::from_rgb(255, 0, 150);         // Rosa caliente
-        let energy_white = ShaderColor::from_rgb(200, 255, 255);   // Blanco energético
-        let warning_orange = ShaderColor::from_rgb(255, 150, 0);   // Naranja advertencia
+        
+        // Dirección en espacio de objeto: todo el muestreo gira con el planeta
+        let object_position = uniforms.object_position(position);
+        let direction = object_position.normalize();
+        let (longitude, latitude) = spherical_uv(direction);
+        let footprint = uniforms.footprint(position);
+        let energy_pulse = self.energy_pulse(position, uniforms.time);
         
         // === CAPA 1: GRILLA TECNOLÓGICA BASE ===
+        // Número entero de divisiones: las líneas coinciden al cerrar la longitud
         let grid_size = 20.0;
-        let grid_x = (uv.0 * grid_size).fract();
-        let grid_y = (uv.1 * grid_size).fract();
+        let grid_x = (longitude * grid_size).fract();
+        let grid_y = (latitude * grid_size).fract();
         let grid_lines = smoothstep(0.02, 0.0, grid_x.min(1.0 - grid_x)) +
                         smoothstep(0.02, 0.0, grid_y.min(1.0 - grid_y));
         
-        // === CAPA 2: CIRCUITOS HEXAGONALES ===
-        let hex_pattern = voronoi_noise(uv.0 * 12.0, uv.1 * 12.0);
-        let hex_cells = smoothstep(0.15, 0.2, hex_pattern);
-        let hex_borders = smoothstep(0.18, 0.22, hex_pattern) - smoothstep(0.22, 0.25, hex_pattern);
-        
-        // === CAPA 3: FLUJO DE DATOS ===
-        let data_flow1 = fbm(
-            uv.0 * 15.0 + uniforms.time * 0.5,
-            uv.1 * 15.0,
-            4
-        );
-        let data_flow2 = fbm(
-            uv.0 * 20.0 - uniforms.time * 0.7,
-            uv.1 * 10.0 + uniforms.time * 0.3,
-            3
-        );
-        let data_streams = smoothstep(0.6, 0.8, data_flow1) + smoothstep(0.65, 0.85, data_flow2);
-        
-        // === CAPA 4: PULSOS DE ENERGÍA ===
-        let pulse_frequency = 5.0;
-        let pulse_wave = (uniforms.time * pulse_frequency + position.length() * 3.0).sin() * 0.5 + 0.5;
-        let pulse_wave2 = (uniforms.time * pulse_frequency * 1.5 - position.length() * 2.0).sin() * 0.5 + 0.5;
-        let energy_pulse = pulse_wave * 0.6 + pulse_wave2 * 0.4;
-        
-        // === CAPA 5: NODOS DE PODER ===
-        let power_nodes = voronoi_noise(uv.0 * 8.0, uv.1 * 8.0);
-        let node_centers = smoothstep(0.08, 0.05, power_nodes);
-        let node_glow = smoothstep(0.15, 0.05, power_nodes);
-        
-        // === CAPA 6: ESCANEO HOLOGRÁFICO ===
-        let scan_line = (uv.1 * 10.0 - uniforms.time * 2.0) % 1.0;
-        let scan_intensity = smoothstep(0.05, 0.0, (scan_line - 0.5).abs());
-        
-        // === CAPA 7: INTERFERENCIA DIGITAL ===
-        let glitch = simple_noise(
-            (uniforms.time * 10.0).floor() * 0.1,
-            (uv.1 * 20.0).floor()
+        // === CAPA 2: PANELES HEXAGONALES ===
+        // Misma métrica que los circuitos de la emisión: los bordes coinciden
+        let hex_pattern = self.noise.cellular3(
+            object_position.x * 4.0,
+            object_position.y * 4.0,
+            object_position.z * 4.0,
+            DistanceMetric::Manhattan,
         );
-        let glitch_effect = if glitch > 0.95 {
-            simple_noise(uv.0 * 100.0 + uniforms.time * 50.0, uv.1) * 0.3
-        } else {
-            0.0
-        };
+        let hex_cells = smoothstep(0.15, 0.2, hex_pattern.f1);
         
-        // === CONSTRUCCIÓN DEL COLOR ===
+        // === CONSTRUCCIÓN DEL COLOR DE LA SUPERFICIE ===
         let mut base_color = base_dark;
         
         // Agregar grilla base
@@ -86,15 +194,9 @@ impl PlanetShader for CrystalPlanetShader {
             base_color = mix_color(base_color, tech_blue, grid_lines * energy_pulse * 0.5);
         }
         
-        // Agregar circuitos hexagonales
-        if hex_borders > 0.1 {
-            let circuit_color = mix_color(cyber_cyan, tech_blue, energy_pulse);
-            base_color = mix_color(base_color, circuit_color, hex_borders * 0.8);
-        }
-        
         // Celdas hexagonales con variación de color
         if hex_cells > 0.5 {
-            let cell_variety = simple_noise(uv.0 * 12.0, uv.1 * 12.0);
+            let cell_variety = hex_pattern.cell_value();
             let cell_color = if cell_variety > 0.7 {
                 electric_purple
             } else if cell_variety > 0.4 {
@@ -105,40 +207,14 @@ impl PlanetShader for CrystalPlanetShader {
             base_color = mix_color(base_color, cell_color, hex_cells * 0.3 * energy_pulse);
         }
         
-        // Agregar flujo de datos
-        if data_streams > 0.5 {
-            let stream_color = mix_color(neon_green, cyber_cyan, (uniforms.time * 2.0).sin() * 0.5 + 0.5);
-            base_color = mix_color(base_color, stream_color, data_streams * 0.7);
-        }
-        
-        // Agregar nodos de poder
-        if node_centers > 0.5 {
-            let node_pulse = (uniforms.time * 4.0 + uv.0 * 20.0).sin() * 0.5 + 0.5;
-            let node_color = mix_color(hot_pink, energy_white, node_pulse);
-            base_color = mix_color(base_color, node_color, node_centers);
-        }
-        
-        if node_glow > 0.3 {
-            base_color = mix_color(base_color, warning_orange, node_glow * 0.4 * energy_pulse);
-        }
-        
-        // Agregar línea de escaneo
-        if scan_intensity > 0.1 {
-            base_color = mix_color(base_color, energy_white, scan_intensity * 0.8);
-        }
-        
-        // Efecto de glitch
-        if glitch_effect > 0.1 {
-            let glitch_color = mix_color(hot_pink, cyber_cyan, glitch);
-            base_color = mix_color(base_color, glitch_color, glitch_effect);
-        }
-        
-        // === CAPA 8: PATRONES FRACTALES ===
-        let fractal = fbm3d(
-            position.x * 10.0 + uniforms.time * 0.1,
-            position.y * 10.0,
-            position.z * 10.0 - uniforms.time * 0.15,
-            5
+        // === CAPA 3: PATRONES FRACTALES ===
+        let fractal = self.noise.fbm3d_filtered(
+            NoiseBasis::Value,
+            object_position.x * 10.0 + uniforms.time * 0.1,
+            object_position.y * 10.0,
+            object_position.z * 10.0 - uniforms.time * 0.15,
+            5,
+            footprint * 10.0,
         );
         if fractal > 0.6 {
             let fractal_color = mix_color(electric_purple, tech_blue, fractal);
@@ -149,23 +225,22 @@ impl PlanetShader for CrystalPlanetShader {
         let light_dir = uniforms.light_direction.normalize();
         let view_dir = (uniforms.camera_position - position).normalize();
         
-        // Iluminación mínima (el planeta se auto-ilumina)
-        let diffuse = normal.dot(&light_dir).max(0.0) * 0.2;
+        // La superficie refleja el sol; los circuitos brillan por sí mismos
+        let diffuse = normal.dot(&light_dir).max(0.0);
+        let ambient = 0.2;
+        let lighting_intensity = ambient + diffuse * 0.9 + energy_pulse * 0.1;
         
         // Rim lighting holográfico
         let rim = (1.0 - view_dir.dot(&normal).abs()).powf(3.0);
         let rim_color = mix_color(cyber_cyan, hot_pink, (uniforms.time * 2.0).sin() * 0.5 + 0.5);
         
-        // Auto-iluminación
-        let self_illumination = 0.8 + energy_pulse * 0.3;
-        
-        let ambient = 0.2;
-        let lighting_intensity = (ambient + diffuse + self_illumination).min(2.0);
+        // === CIRCUITOS EMISIVOS: más intensos en el lado nocturno ===
+        let glow = self.night_circuit_glow(position, normal, uniforms);
         
         let mut final_color = ShaderColor::new(
-            base_color.r * lighting_intensity,
-            base_color.g * lighting_intensity,
-            base_color.b * lighting_intensity,
+            base_color.r * lighting_intensity + glow.r,
+            base_color.g * lighting_intensity + glow.g,
+            base_color.b * lighting_intensity + glow.b,
             1.0,
         );
         
@@ -174,11 +249,33 @@ impl PlanetShader for CrystalPlanetShader {
             final_color = mix_color(final_color, rim_color, rim * 0.8);
         }
         
-        ShaderColor::new(
+        let color = ShaderColor::new(
             final_color.r.clamp(0.0, 1.0),
             final_color.g.clamp(0.0, 1.0),
             final_color.b.clamp(0.0, 1.0),
             1.0,
-        )
+        );
+        (color, glow)
+    }
+
+    fn emission(&self, position: Vector3, normal: Vector3, uniforms: &ShaderUniforms) -> ShaderColor {
+        self.night_circuit_glow(position, normal, uniforms)
+    }
+}
+
+pub fn descriptor() -> PlanetDescriptor {
+    PlanetDescriptor {
+        id: "sci_fi",
+        name: "Planeta Sci-Fi",
+        description: "Circuitos y energía tecnológica",
+        icon: "⚡",
+        color: ShaderColor::from_rgb(0, 255, 255),
+        rotation_speed: 1.2,
+        scene_scale: 0.9,
+        axial_tilt: 0.2,
+        precession_rate: 0.3,
+        axis_longitude: 0.0,
+        default_params: ShaderParams::new().with("seed", 3.0),
+        factory: |params| Box::new(CrystalPlanetShader::new(params.seed())),
     }
 }
@@ -4,16 +4,23 @@
 // ============================================================================
 
 use crate::vector::Vector3;
-use crate::shaders::{ShaderColor, ShaderUniforms, PlanetShader, fbm, smoothstep, mix_color};
+use crate::shaders::{ShaderColor, ShaderUniforms, ShaderParams, PlanetShader, NoiseSource, NoiseNode, NoiseNodeExt, BasisNode, NoiseBasis, ColorRamp, RampInterpolation, RampColorSpace, smoothstep, mix_color, spherical_uv, longitude_cylinder, wrap_delta};
+use crate::rings::RingShader;
+use super::registry::PlanetDescriptor;
 
-pub struct GasGiantShader;
+/// Velocidad con la que cambian de forma los remolinos (unidades de ruido por segundo)
+const SWIRL_EVOLUTION: f32 = 0.02;
 
-impl PlanetShader for GasGiantShader {
-    fn vertex_shader(&self, position: Vector3, normal: Vector3, _uv: (f32, f32), _uniforms: &ShaderUniforms) -> (Vector3, Vector3) {
-        (position, normal)
-    }
+pub struct GasGiantShader {
+    swirls: Box<dyn NoiseNode>,
+    /// Ruido 4D que desplaza el punto de muestreo de los remolinos con el tiempo
+    swirl_flow: NoiseSource,
+    band_ramps: Vec<ColorRamp>,
+    rings: Option<RingShader>,
+}
 
-    fn fragment_shader(&self, _position: Vector3, normal: Vector3, uv: (f32, f32), uniforms: &ShaderUniforms) -> ShaderColor {
+impl GasGiantShader {
+    pub fn new(seed: u32) -> Self {
         // === PALETA DE JÚPITER - MUY CONTRASTADA ===
         let very_dark = ShaderColor::from_rgb(60, 35, 15);         // Casi negro
         let dark_brown = ShaderColor::from_rgb(110, 65, 25);       // Marrón oscuro
@@ -23,60 +30,96 @@ impl PlanetShader for GasGiantShader {
         let beige = ShaderColor::from_rgb(230, 190, 130);          // Beige
         let cream = ShaderColor::from_rgb(245, 220, 170);          // Crema
         let white = ShaderColor::from_rgb(255, 250, 230);          // Blanco
+        
+        // Una rampa por banda (alternando oscuro/claro)
+        let band_ramps = [
+            [very_dark, dark_brown],
+            [tan, beige],
+            [dark_brown, rust_brown],
+            [cream, white],
+            [rust_brown, orange],
+            [beige, cream],
+            [orange, tan],
+            [white, cream],
+        ]
+        .iter()
+        .map(|colors| ColorRamp::from_colors(colors, RampInterpolation::Linear, RampColorSpace::Srgb))
+        .collect();
+        
+        // Remolinos de Júpiter: fbm simplex deformado por otro fbm; se evalúa sobre
+        // el cilindro de longitud, así que no hay costura al dar la vuelta
+        let swirls = BasisNode::new(seed, NoiseBasis::Simplex)
+            .fbm(5, 2.0, 0.5)
+            .warp(BasisNode::new(seed.wrapping_add(1), NoiseBasis::Simplex).fbm(3, 2.0, 0.5), 0.6, 2)
+            .remap(-0.6, 0.6, 0.0, 0.48)
+            .clamp(0.0, 0.48)
+            .boxed();
+        
+        GasGiantShader {
+            swirls,
+            swirl_flow: NoiseSource::new(seed.wrapping_add(2)),
+            band_ramps,
+            rings: None,
+        }
+    }
+    
+    /// Añade un sistema de anillos alrededor del planeta
+    pub fn with_rings(mut self, rings: RingShader) -> Self {
+        self.rings = Some(rings);
+        self
+    }
+}
+
+impl PlanetShader for GasGiantShader {
+    fn vertex_shader(&self, position: Vector3, normal: Vector3, _uv: (f32, f32), _uniforms: &ShaderUniforms) -> (Vector3, Vector3) {
+        (position, normal)
+    }
+
+    fn fragment_shader(&self, position: Vector3, normal: Vector3, _uv: (f32, f32), uniforms: &ShaderUniforms) -> ShaderColor {
+        // === COLORES DE LAS TORMENTAS ===
+        let orange = ShaderColor::from_rgb(200, 120, 50);          // Naranja
+        let cream = ShaderColor::from_rgb(245, 220, 170);          // Crema
+        let white = ShaderColor::from_rgb(255, 250, 230);          // Blanco
         let red_spot = ShaderColor::from_rgb(200, 60, 30);         // Rojo intenso
         
-        let latitude = uv.1;
-        let animated_longitude = uv.0 + uniforms.time * 0.015;
+        // Coordenadas esféricas desde la dirección de la superficie (no desde las UV de la malla)
+        let object_position = uniforms.object_position(position);
+        let (longitude, latitude) = spherical_uv(object_position.normalize());
+        let animated_longitude = (longitude + uniforms.time * 0.015).rem_euclid(1.0);
         
         // === BANDAS BASE CON TEXTURA ===
         // Sistema de bandas alternadas (14 bandas como Júpiter real)
         let band_pos = latitude * 14.0;
-        let band_index = band_pos.floor() as i32 % 8;
+        let band_index = (band_pos.floor() as i32).rem_euclid(self.band_ramps.len() as i32) as usize;
         let band_fract = band_pos.fract();
         
-        // TURBULENCIA HORIZONTAL FUERTE (corrientes de chorro)
-        let jet_stream = fbm(
-            animated_longitude * 12.0,
-            latitude * 5.0,
-            5
-        ) * 0.25; // Más turbulencia
-        
-        // Remolinos y vórtices a lo largo de las bandas
-        let vortices = fbm(
-            animated_longitude * 8.0 + latitude * 20.0,
-            latitude * 8.0,
-            4
-        ) * 0.15;
-        
-        // Textura fina (nubes pequeñas)
-        let fine_texture = fbm(
-            animated_longitude * 25.0,
-            latitude * 20.0,
-            3
-        ) * 0.08;
-        
-        // Combinar todas las texturas
-        let texture_value = jet_stream + vortices + fine_texture;
+        // TURBULENCIA: corrientes de chorro, remolinos y nubes finas en una sola expresión
+        // Huella del píxel en el cilindro: 12 periodos por vuelta (2π rad) y 5 de polo a polo (π rad)
+        let footprint = uniforms.footprint(position) / object_position.length()
+            * (12.0 / std::f32::consts::TAU).max(5.0 / std::f32::consts::PI);
+        let cylinder = longitude_cylinder(animated_longitude, latitude, 12.0, 5.0);
+        // El tiempo entra como cuarta coordenada de un fbm 4D que desplaza el punto
+        // sobre el cilindro: los remolinos cambian de forma en lugar de solo derivar
+        let flow = self.swirl_flow.fbm4d_filtered(
+            cylinder.x * 0.5,
+            cylinder.y * 0.5,
+            cylinder.z * 0.5,
+            uniforms.time * SWIRL_EVOLUTION,
+            2,
+            footprint * 0.5,
+        );
+        let texture_value = self.swirls.eval_filtered(cylinder + Vector3::new(0.0, 0.0, flow * 0.8), footprint);
         let final_band = (band_fract + texture_value).clamp(0.0, 1.0);
         
         // === COLORES POR BANDA (Alternando oscuro/claro) ===
-        let base_color = match band_index {
-            0 => mix_color(very_dark, dark_brown, final_band),
-            1 => mix_color(tan, beige, final_band),
-            2 => mix_color(dark_brown, rust_brown, final_band),
-            3 => mix_color(cream, white, final_band),
-            4 => mix_color(rust_brown, orange, final_band),
-            5 => mix_color(beige, cream, final_band),
-            6 => mix_color(orange, tan, final_band),
-            _ => mix_color(white, cream, final_band),
-        };
+        let base_color = self.band_ramps[band_index].sample(final_band);
         
         let mut final_color = base_color;
         
         // === GRAN MANCHA ROJA (más grande y visible) ===
         let storm_x = 0.3;
         let storm_y = 0.4;
-        let dx = (animated_longitude - storm_x) * 2.5; // Elipse horizontal
+        let dx = wrap_delta(animated_longitude, storm_x) * 2.5; // Elipse horizontal
         let dy = latitude - storm_y;
         let dist_storm = (dx * dx + dy * dy).sqrt();
         
@@ -90,13 +133,13 @@ impl PlanetShader for GasGiantShader {
         }
         
         // === ÓVALOS BLANCOS (tormentas menores) ===
-        let oval1_dist = ((animated_longitude - 0.6).powi(2) * 4.0 + (latitude - 0.55).powi(2)).sqrt();
+        let oval1_dist = (wrap_delta(animated_longitude, 0.6).powi(2) * 4.0 + (latitude - 0.55).powi(2)).sqrt();
         if oval1_dist < 0.05 {
             let oval_str = smoothstep(0.05, 0.02, oval1_dist);
             final_color = mix_color(final_color, white, oval_str * 0.8);
         }
         
-        let oval2_dist = ((animated_longitude - 0.75).powi(2) * 5.0 + (latitude - 0.32).powi(2)).sqrt();
+        let oval2_dist = (wrap_delta(animated_longitude, 0.75).powi(2) * 5.0 + (latitude - 0.32).powi(2)).sqrt();
         if oval2_dist < 0.04 {
             let oval_str = smoothstep(0.04, 0.015, oval2_dist);
             final_color = mix_color(final_color, cream, oval_str * 0.7);
@@ -105,8 +148,14 @@ impl PlanetShader for GasGiantShader {
         // === ILUMINACIÓN ===
         let light_dir = uniforms.light_direction.normalize();
         let diffuse = normal.dot(&light_dir).max(0.0);
+        // Los anillos proyectan su sombra sobre las nubes; su plano es el
+        // ecuador, así que el rayo se corta en espacio de objeto
+        let ring_shadow = self.rings.as_ref().map_or(1.0, |rings| {
+            let object_light = uniforms.world_to_object.apply_to_direction(&light_dir).normalize();
+            rings.shadow_on_planet(uniforms.object_position(position), object_light)
+        });
         let ambient = 0.4;
-        let lighting = (ambient + diffuse * 0.6).min(1.0);
+        let lighting = (ambient + diffuse * 0.6 * ring_shadow).min(1.0);
         
         ShaderColor::new(
             (final_color.r * lighting).clamp(0.0, 1.0),
@@ -115,4 +164,45 @@ impl PlanetShader for GasGiantShader {
             1.0,
         )
     }
+    
+    fn rings(&self) -> Option<&RingShader> {
+        self.rings.as_ref()
+    }
+
+    fn color_ramps_mut(&mut self) -> Vec<&mut ColorRamp> {
+        self.band_ramps.iter_mut().collect()
+    }
+}
+
+pub fn descriptor() -> PlanetDescriptor {
+    PlanetDescriptor {
+        id: "gas_giant",
+        name: "Gigante Gaseoso",
+        description: "Bandas atmosféricas y anillos",
+        icon: "🌍",
+        color: ShaderColor::from_rgb(200, 120, 50),
+        rotation_speed: 0.8,
+        scene_scale: 1.8,
+        axial_tilt: 0.45,
+        precession_rate: 0.0,
+        axis_longitude: -0.8,
+        default_params: ShaderParams::new()
+            .with("seed", 2.0)
+            .with("rings", 1.0)
+            .with("ring_inner", 1.3)
+            .with("ring_outer", 2.3),
+        factory: |params| {
+            let shader = GasGiantShader::new(params.seed());
+            if params.get("rings", 1.0) > 0.5 {
+                let rings = RingShader::new(
+                    params.seed().wrapping_add(3),
+                    params.get("ring_inner", 1.3),
+                    params.get("ring_outer", 2.3),
+                );
+                Box::new(shader.with_rings(rings))
+            } else {
+                Box::new(shader)
+            }
+        },
+    }
 }
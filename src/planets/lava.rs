@@ -0,0 +1,184 @@
+// ============================================================================
+// PLANETA 8: MUNDO DE LAVA
+// Características: Placas de corteza fría (Voronoi), grietas de magma emisivo
+// que pulsan, calima sobre las grietas y resplandor en el lado nocturno
+// ============================================================================
+
+use crate::vector::Vector3;
+use crate::shaders::{ShaderColor, ShaderUniforms, ShaderParams, PlanetShader, NoiseSource, NoiseBasis, DistanceMetric, smoothstep, mix, mix_color, displaced_normal, blackbody_color};
+use super::registry::PlanetDescriptor;
+
+/// Número aproximado de placas a lo largo de un radio de la esfera unitaria
+const PLATE_SCALE: f32 = 5.0;
+
+/// Relieve de las placas sobre el fondo de las grietas (en unidades del objeto)
+const PLATE_RELIEF: f32 = 0.012;
+
+/// Corteza muestreada en un punto de la esfera
+struct CrustSample {
+    /// 1 en el centro de una grieta, 0 sobre la placa
+    crack: f32,
+    /// Borde caliente alrededor de la grieta, más ancho que la grieta
+    heat: f32,
+    /// Valor aleatorio estable para toda la placa
+    plate: f32,
+}
+
+pub struct LavaPlanetShader {
+    noise: NoiseSource,
+    crack_width: f32,
+}
+
+impl LavaPlanetShader {
+    pub fn new(seed: u32, crack_width: f32) -> Self {
+        LavaPlanetShader {
+            noise: NoiseSource::new(seed),
+            crack_width: crack_width.max(0.01),
+        }
+    }
+
+    /// Placas Voronoi con el dominio deformado para que las grietas no sean rectas.
+    /// `footprint` ensancha las grietas más finas que un píxel sin perder su energía
+    fn crust(&self, direction: Vector3, footprint: f32) -> CrustSample {
+        let warp = Vector3::new(
+            self.noise.fbm3d_basis(NoiseBasis::Simplex, direction.x * 3.0, direction.y * 3.0, direction.z * 3.0, 3),
+            self.noise.fbm3d_basis(NoiseBasis::Simplex, direction.x * 3.0 + 5.2, direction.y * 3.0 + 1.3, direction.z * 3.0 + 7.9, 3),
+            self.noise.fbm3d_basis(NoiseBasis::Simplex, direction.x * 3.0 + 1.7, direction.y * 3.0 + 9.2, direction.z * 3.0 + 3.4, 3),
+        );
+        let p = direction * PLATE_SCALE + warp * 0.6;
+        let cell = self.noise.cellular3(p.x, p.y, p.z, DistanceMetric::Euclidean);
+        let edge = cell.edge_distance();
+
+        let width = self.crack_width + footprint * PLATE_SCALE;
+        let crack = (1.0 - smoothstep(0.0, width, edge)) * (self.crack_width / width);
+        let heat = 1.0 - smoothstep(0.0, width * 3.0, edge);
+
+        CrustSample {
+            crack,
+            heat,
+            plate: cell.cell_value(),
+        }
+    }
+
+    /// Brillo del magma: cada placa late con su propia fase sobre un latido global lento
+    fn pulse(&self, plate: f32, time: f32) -> f32 {
+        let local = (time * 1.7 + plate * std::f32::consts::TAU).sin();
+        let global = (time * 0.6).sin();
+        0.8 + local * 0.15 + global * 0.1
+    }
+
+    /// Luz propia del magma y del borde caliente de la corteza
+    fn magma_emission(&self, crust: &CrustSample, time: f32) -> ShaderColor {
+        let pulse = self.pulse(crust.plate, time);
+
+        // Más caliente (y más amarillo) en el centro de la grieta
+        let magma = blackbody_color(mix(1000.0, 1900.0, crust.crack * pulse));
+        let glow = crust.crack.powf(1.5) * pulse * 1.4;
+
+        // La roca junto a la grieta brilla rojo oscuro aunque no le dé el sol
+        let rim = crust.heat * 0.2 * pulse;
+
+        ShaderColor::new(
+            magma.r * glow + rim * 0.9,
+            magma.g * glow + rim * 0.15,
+            magma.b * glow,
+            1.0,
+        )
+    }
+
+    /// Las placas sobresalen y las grietas quedan hundidas
+    fn surface_displacement(&self, position: Vector3) -> f32 {
+        (1.0 - self.crust(position.normalize(), 0.0).crack) * PLATE_RELIEF
+    }
+}
+
+impl PlanetShader for LavaPlanetShader {
+    fn vertex_shader(&self, position: Vector3, normal: Vector3, _uv: (f32, f32), uniforms: &ShaderUniforms) -> (Vector3, Vector3) {
+        let direction = position.normalize();
+        let crust = self.crust(direction, 0.0);
+
+        // Calima: el aire caliente sobre las grietas hace temblar la superficie
+        let haze = self.noise.simplex4(
+            direction.x * 12.0,
+            direction.y * 12.0,
+            direction.z * 12.0,
+            uniforms.time * 3.0,
+        ) * 0.008 * crust.heat;
+
+        let relief = (1.0 - crust.crack) * PLATE_RELIEF;
+        let displaced_position = position + normal * (relief + haze);
+        let terrain_normal = displaced_normal(position, normal, 0.01, |p| self.surface_displacement(p));
+        (displaced_position, terrain_normal)
+    }
+
+    fn fragment_shader(&self, position: Vector3, normal: Vector3, uv: (f32, f32), uniforms: &ShaderUniforms) -> ShaderColor {
+        self.shade(position, normal, uv, uniforms).0
+    }
+
+    /// Una sola muestra de la corteza da el color de la roca y la luz del magma
+    fn shade(&self, position: Vector3, normal: Vector3, _uv: (f32, f32), uniforms: &ShaderUniforms) -> (ShaderColor, ShaderColor) {
+        // === PALETA DE CORTEZA VOLCÁNICA ===
+        let basalt = ShaderColor::from_rgb(28, 24, 24);           // Basalto frío
+        let ash = ShaderColor::from_rgb(62, 52, 48);              // Ceniza
+        let scorched = ShaderColor::from_rgb(85, 30, 15);         // Roca chamuscada
+
+        let object_position = uniforms.object_position(position);
+        let direction = object_position.normalize();
+        let footprint = uniforms.footprint(position) / object_position.length();
+        let crust = self.crust(direction, footprint);
+
+        // === CORTEZA ===
+        let rock_detail = self.noise.fbm3d_filtered(
+            NoiseBasis::Value,
+            object_position.x * 14.0,
+            object_position.y * 14.0,
+            object_position.z * 14.0,
+            4,
+            footprint * 14.0,
+        );
+        // Cada placa tiene un tono ligeramente distinto
+        let crust_color = mix_color(basalt, ash, rock_detail * 0.7 + crust.plate * 0.3);
+        let crust_color = mix_color(crust_color, scorched, crust.heat * 0.6);
+
+        // === ILUMINACIÓN (solo la corteza refleja el sol) ===
+        let light_dir = uniforms.light_direction.normalize();
+        let diffuse = normal.dot(&light_dir).max(0.0);
+        let ambient = 0.08;
+        let lighting = ambient + diffuse * 0.85;
+
+        // === MAGMA: emisivo, visible también en el lado nocturno ===
+        let emission = self.magma_emission(&crust, uniforms.time);
+
+        let color = ShaderColor::new(
+            (crust_color.r * lighting + emission.r).clamp(0.0, 1.0),
+            (crust_color.g * lighting + emission.g).clamp(0.0, 1.0),
+            (crust_color.b * lighting + emission.b).clamp(0.0, 1.0),
+            1.0,
+        );
+        (color, emission)
+    }
+
+    fn emission(&self, position: Vector3, _normal: Vector3, uniforms: &ShaderUniforms) -> ShaderColor {
+        let object_position = uniforms.object_position(position);
+        let footprint = uniforms.footprint(position) / object_position.length();
+        let crust = self.crust(object_position.normalize(), footprint);
+        self.magma_emission(&crust, uniforms.time)
+    }
+}
+
+pub fn descriptor() -> PlanetDescriptor {
+    PlanetDescriptor {
+        id: "lava",
+        name: "Planeta de Lava",
+        description: "Corteza agrietada sobre magma",
+        icon: "🌋",
+        color: ShaderColor::from_rgb(255, 90, 20),
+        rotation_speed: 0.5,
+        scene_scale: 0.8,
+        axial_tilt: 0.0,
+        precession_rate: 0.0,
+        axis_longitude: 0.0,
+        default_params: ShaderParams::new().with("seed", 8.0).with("crack_width", 0.12),
+        factory: |params| Box::new(LavaPlanetShader::new(params.seed(), params.get("crack_width", 0.12))),
+    }
+}
@@ -4,20 +4,45 @@
 // ============================================================================
 
 use crate::vector::Vector3;
-use crate::shaders::{ShaderColor, ShaderUniforms, PlanetShader, fbm, fbm3d, voronoi_noise, smoothstep, mix_color};
+use crate::shaders::{ShaderColor, ShaderUniforms, ShaderParams, PlanetShader, NoiseSource, NoiseBasis, DistanceMetric, ColorRamp, RampInterpolation, RampColorSpace, smoothstep, mix_color, triplanar, displaced_normal};
+use super::registry::PlanetDescriptor;
 
-pub struct SaturnShader;
+pub struct MetallicPlanetShader {
+    noise: NoiseSource,
+    metal_ramp: ColorRamp,
+}
 
-impl PlanetShader for SaturnShader {
-    fn vertex_shader(&self, position: Vector3, normal: Vector3, _uv: (f32, f32), uniforms: &ShaderUniforms) -> (Vector3, Vector3) {
+impl MetallicPlanetShader {
+    pub fn new(seed: u32) -> Self {
+        // Paleta metálica; el acento oxidado termina en un corte duro en 0.2
+        let dark_metal = ShaderColor::from_rgb(40, 45, 50);        // Metal oscuro
+        let metal_ramp = ColorRamp::new(RampInterpolation::Linear, RampColorSpace::Srgb)
+            .with_stop(0.0, dark_metal)
+            .with_stop(0.2, ShaderColor::from_rgb(120, 80, 60))    // Acento oxidado
+            .with_stop(0.2, dark_metal)
+            .with_stop(0.4, ShaderColor::from_rgb(80, 90, 100))    // Acero
+            .with_stop(0.6, ShaderColor::from_rgb(140, 150, 160))  // Plata oscura
+            .with_stop(0.8, ShaderColor::from_rgb(200, 210, 220))  // Plata brillante
+            .with_stop(1.0, ShaderColor::from_rgb(240, 245, 250)); // Cromado
+        
+        MetallicPlanetShader {
+            noise: NoiseSource::new(seed),
+            metal_ramp,
+        }
+    }
+
+    /// Altura de los picos a lo largo de la normal en un punto de la esfera
+    fn spike_height(&self, position: Vector3, time: f32) -> f32 {
         // === GENERAR PICOS/PÚAS PROCEDURALMENTE ===
         
-        // CAPA 1: Picos grandes principales (usando Voronoi para distribución)
+        // CAPA 1: Picos grandes principales (ruido celular 3D para distribución)
         let voronoi_scale = 15.0;
-        let voronoi_pattern = voronoi_noise(
-            position.x * voronoi_scale + position.z * voronoi_scale,
-            position.y * voronoi_scale
-        );
+        let voronoi_pattern = self.noise.cellular3(
+            position.x * voronoi_scale,
+            position.y * voronoi_scale,
+            position.z * voronoi_scale,
+            DistanceMetric::Euclidean,
+        ).f1;
         
         // Los picos se generan donde el Voronoi es pequeño (centros de células)
         let spike_large = if voronoi_pattern < 0.15 {
@@ -27,10 +52,12 @@ impl PlanetShader for SaturnShader {
         };
         
         // CAPA 2: Picos medianos (más densidad)
-        let voronoi_medium = voronoi_noise(
-            position.x * 25.0 + position.z * 25.0,
-            position.y * 25.0
-        );
+        let voronoi_medium = self.noise.cellular3(
+            position.x * 25.0,
+            position.y * 25.0,
+            position.z * 25.0,
+            DistanceMetric::Euclidean,
+        ).f1;
         
         let spike_medium = if voronoi_medium < 0.12 {
             smoothstep(0.12, 0.04, voronoi_medium) * 0.25
@@ -39,10 +66,12 @@ impl PlanetShader for SaturnShader {
         };
         
         // CAPA 3: Picos pequeños (muy densos, como púas)
-        let voronoi_small = voronoi_noise(
-            position.x * 40.0 + uniforms.time * 0.1 + position.z * 40.0,
-            position.y * 40.0
-        );
+        let voronoi_small = self.noise.cellular3(
+            position.x * 40.0 + time * 0.1,
+            position.y * 40.0,
+            position.z * 40.0,
+            DistanceMetric::Euclidean,
+        ).f1;
         
         let spike_small = if voronoi_small < 0.1 {
             smoothstep(0.1, 0.03, voronoi_small) * 0.15
@@ -51,7 +80,7 @@ impl PlanetShader for SaturnShader {
         };
         
         // CAPA 4: Rugosidad base (textura áspera metálica)
-        let roughness = fbm3d(
+        let roughness = self.noise.fbm3d(
             position.x * 50.0,
             position.y * 50.0,
             position.z * 50.0,
@@ -59,70 +88,75 @@ impl PlanetShader for SaturnShader {
         ) * 0.05;
         
         // CAPA 5: Deformación animada (pulsación metálica)
-        let pulse = (uniforms.time * 2.0 + position.length() * 5.0).sin() * 0.02;
+        let pulse = (time * 2.0 + position.length() * 5.0).sin() * 0.02;
         
         // Combinar todas las capas de picos
-        let total_displacement = spike_large + spike_medium + spike_small + roughness + pulse;
-        
+        spike_large + spike_medium + spike_small + roughness + pulse
+    }
+}
+
+impl PlanetShader for MetallicPlanetShader {
+    fn vertex_shader(&self, position: Vector3, normal: Vector3, _uv: (f32, f32), uniforms: &ShaderUniforms) -> (Vector3, Vector3) {
         // Desplazar el vértice a lo largo de la normal (hacia afuera)
+        let total_displacement = self.spike_height(position, uniforms.time);
         let displaced_position = position + normal * total_displacement;
         
-        // Recalcular la normal basada en los picos
-        // (aproximación: la normal apunta más hacia afuera donde hay picos)
-        let spike_factor = spike_large * 2.0 + spike_medium * 1.5 + spike_small;
-        let adjusted_normal = (normal + normal * spike_factor).normalize();
+        // Normal de los picos a partir del mismo campo de desplazamiento;
+        // el paso es menor que las púas más pequeñas para no suavizarlas
+        let adjusted_normal = displaced_normal(position, normal, 0.004, |p| self.spike_height(p, uniforms.time));
         
         (displaced_position, adjusted_normal)
     }
 
-    fn fragment_shader(&self, position: Vector3, normal: Vector3, uv: (f32, f32), uniforms: &ShaderUniforms) -> ShaderColor {
-        // === PALETA METÁLICA ===
-        let dark_metal = ShaderColor::from_rgb(40, 45, 50);        // Metal oscuro
-        let medium_metal = ShaderColor::from_rgb(80, 90, 100);     // Acero
-        let light_metal = ShaderColor::from_rgb(140, 150, 160);    // Plata oscura
-        let bright_metal = ShaderColor::from_rgb(200, 210, 220);   // Plata brillante
-        let chrome = ShaderColor::from_rgb(240, 245, 250);         // Cromado
-        let rust_accent = ShaderColor::from_rgb(120, 80, 60);      // Acento oxidado
+    fn color_ramps_mut(&mut self) -> Vec<&mut ColorRamp> {
+        vec![&mut self.metal_ramp]
+    }
+
+    fn fragment_shader(&self, position: Vector3, normal: Vector3, _uv: (f32, f32), uniforms: &ShaderUniforms) -> ShaderColor {
+        let chrome = ShaderColor::from_rgb(240, 245, 250);         // Cromado para los brillos
         
         // === TEXTURA METÁLICA PROCEDURAL ===
-        
-        // Patrón Voronoi para variación metálica
-        let metal_pattern = voronoi_noise(
-            position.x * 20.0 + position.z * 20.0,
-            position.y * 20.0
-        );
+        // Muestrear en espacio de objeto para que la textura gire con el planeta
+        let object_position = uniforms.object_position(position);
+        let footprint = uniforms.footprint(position);
+        
+        // Patrón Voronoi para variación metálica: Chebyshev da placas cuadradas
+        let metal_pattern = self.noise.cellular3(
+            object_position.x * 20.0,
+            object_position.y * 20.0,
+            object_position.z * 20.0,
+            DistanceMetric::Chebyshev,
+        ).f1;
         
         // Ruido para imperfecciones metálicas
-        let imperfections = fbm3d(
-            position.x * 30.0,
-            position.y * 30.0,
-            position.z * 30.0,
-            4
+        let imperfections = self.noise.fbm3d_filtered(
+            NoiseBasis::Value,
+            object_position.x * 30.0,
+            object_position.y * 30.0,
+            object_position.z * 30.0,
+            4,
+            footprint * 30.0,
         );
         
         // Rayones y arañazos (scratch pattern)
-        let scratches = fbm(
-            uv.0 * 100.0,
-            uv.1 * 100.0,
-            3
-        );
+        // (se desvanecen hacia su media cuando el planeta se ve pequeño)
+        let scratch_footprint = footprint / object_position.length() * 100.0;
+        let scratches = triplanar(object_position.normalize(), 100.0, 4.0, |a, b| {
+            self.noise.fbm_filtered(a, b, 3, scratch_footprint)
+        });
         
         // Combinar para obtener color base metálico
         let metal_value = (metal_pattern + imperfections * 0.5 + scratches * 0.3 + 1.5) / 3.0;
         
-        let base_color = if metal_value > 0.8 {
-            mix_color(chrome, bright_metal, (1.0 - metal_value) * 5.0)
-        } else if metal_value > 0.6 {
-            mix_color(bright_metal, light_metal, (0.8 - metal_value) * 5.0)
-        } else if metal_value > 0.4 {
-            mix_color(light_metal, medium_metal, (0.6 - metal_value) * 5.0)
-        } else if metal_value > 0.2 {
-            mix_color(medium_metal, dark_metal, (0.4 - metal_value) * 5.0)
-        } else {
-            mix_color(dark_metal, rust_accent, metal_value * 5.0)
-        };
+        let base_color = self.metal_ramp.sample(metal_value);
         
         // === ILUMINACIÓN METÁLICA ===
+        // Bump por píxel: las imperfecciones abollan ligeramente la normal
+        let normal = displaced_normal(position, normal, 0.005, |p| {
+            let q = uniforms.object_position(p);
+            self.noise.fbm3d_filtered(NoiseBasis::Value, q.x * 30.0, q.y * 30.0, q.z * 30.0, 2, footprint * 30.0) * 0.004
+        });
+        
         let light_dir = uniforms.light_direction.normalize();
         let view_dir = (uniforms.camera_position - position).normalize();
         
@@ -171,3 +205,20 @@ impl PlanetShader for SaturnShader {
         )
     }
 }
+
+pub fn descriptor() -> PlanetDescriptor {
+    PlanetDescriptor {
+        id: "metallic",
+        name: "Planeta Metálico",
+        description: "Superficie con picos metálicos",
+        icon: "⚙️",
+        color: ShaderColor::from_rgb(200, 210, 220),
+        rotation_speed: 0.6,
+        scene_scale: 0.8,
+        axial_tilt: 0.1,
+        precession_rate: 0.1,
+        axis_longitude: 0.0,
+        default_params: ShaderParams::new().with("seed", 5.0),
+        factory: |params| Box::new(MetallicPlanetShader::new(params.seed())),
+    }
+}
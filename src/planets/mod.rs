@@ -3,15 +3,40 @@
 // Cada planeta tiene su propia implementación en archivos separados
 // ============================================================================
 
+pub mod registry;
 pub mod rocky;
 pub mod gas_giant;
 pub mod crystal;
 pub mod nebula;
 pub mod metallic;
+pub mod terrestrial;
+pub mod star;
+pub mod lava;
+pub mod asteroid;
 
-// Re-exportar los shaders para facilitar su uso
-pub use rocky::RockyPlanetShader;
-pub use gas_giant::GasGiantShader;
-pub use crystal::CrystalPlanetShader;
-pub use nebula::LavaPlanetShader;
-pub use metallic::SaturnShader;
+// Re-exportar el registro para facilitar su uso
+pub use registry::{PlanetDescriptor, PlanetRegistry};
+
+/// Crea el registro con todos los planetas incluidos en el proyecto
+pub fn builtin_registry() -> PlanetRegistry {
+    let mut registry = PlanetRegistry::new();
+    let builtin = [
+        rocky::descriptor(),
+        gas_giant::descriptor(),
+        crystal::descriptor(),
+        nebula::descriptor(),
+        metallic::descriptor(),
+        terrestrial::descriptor(),
+        star::descriptor(),
+        lava::descriptor(),
+        asteroid::descriptor(),
+    ];
+
+    for descriptor in builtin {
+        registry
+            .register(descriptor)
+            .expect("ERROR: id de planeta duplicado en el registro integrado");
+    }
+
+    registry
+}
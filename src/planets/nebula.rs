@@ -4,11 +4,22 @@
 // ============================================================================
 
 use crate::vector::Vector3;
-use crate::shaders::{ShaderColor, ShaderUniforms, PlanetShader, fbm, fbm3d, voronoi_noise, ridge_noise, simple_noise, smoothstep, mix_color};
+use crate::shaders::{ShaderColor, ShaderUniforms, ShaderParams, PlanetShader, NoiseSource, NoiseBasis, DistanceMetric, smoothstep, mix_color, spherical_uv, triplanar};
+use super::registry::PlanetDescriptor;
 
-pub struct LavaPlanetShader;
+pub struct NebulaPlanetShader {
+    noise: NoiseSource,
+}
 
-impl PlanetShader for LavaPlanetShader {
+impl NebulaPlanetShader {
+    pub fn new(seed: u32) -> Self {
+        NebulaPlanetShader {
+            noise: NoiseSource::new(seed),
+        }
+    }
+}
+
+impl PlanetShader for NebulaPlanetShader {
     fn vertex_shader(&self, position: Vector3, normal: Vector3, _uv: (f32, f32), uniforms: &ShaderUniforms) -> (Vector3, Vector3) {
         // Ondulación suave como gas etéreo
         let wave1 = (uniforms.time * 1.5 + position.x * 3.0 + position.y * 2.0).sin() * 0.03;
@@ -17,7 +28,7 @@ impl PlanetShader for LavaPlanetShader {
         (wavy_position, normal)
     }
 
-    fn fragment_shader(&self, position: Vector3, normal: Vector3, uv: (f32, f32), uniforms: &ShaderUniforms) -> ShaderColor {
+    fn fragment_shader(&self, position: Vector3, normal: Vector3, _uv: (f32, f32), uniforms: &ShaderUniforms) -> ShaderColor {
         // === PALETA NEBULOSA CÓSMICA ===
         let void_black = ShaderColor::from_rgb(5, 0, 10);          // Vacío espacial
         let deep_purple = ShaderColor::from_rgb(30, 0, 60);        // Púrpura profundo
@@ -30,66 +41,83 @@ impl PlanetShader for LavaPlanetShader {
         let yellow_star = ShaderColor::from_rgb(255, 255, 100);    // Amarillo estelar
         let white_hot = ShaderColor::from_rgb(255, 255, 255);      // Blanco caliente
         
+        // Muestrear en espacio de objeto para que la nebulosa gire con el planeta
+        let object_position = uniforms.object_position(position);
+        let direction = object_position.normalize();
+        
+        // Huella del píxel: las octavas finas se apagan cuando la nebulosa se ve lejos
+        let footprint = uniforms.footprint(position);
+        let direction_footprint = footprint / object_position.length();
+        
         // === CAPA 1: GAS NEBULAR BASE (Movimiento lento y fluido) ===
-        let nebula_gas1 = fbm3d(
-            position.x * 2.0 + uniforms.time * 0.03,
-            position.y * 2.0 + uniforms.time * 0.02,
-            position.z * 2.0 - uniforms.time * 0.025,
-            7
+        let nebula_gas1 = self.noise.fbm3d_filtered(
+            NoiseBasis::Value,
+            object_position.x * 2.0 + uniforms.time * 0.03,
+            object_position.y * 2.0 + uniforms.time * 0.02,
+            object_position.z * 2.0 - uniforms.time * 0.025,
+            7,
+            footprint * 2.0,
         );
-        let nebula_gas2 = fbm3d(
-            position.x * 3.0 - uniforms.time * 0.02,
-            position.y * 3.0 + uniforms.time * 0.035,
-            position.z * 3.0 + uniforms.time * 0.015,
-            6
+        let nebula_gas2 = self.noise.fbm3d_filtered(
+            NoiseBasis::Value,
+            object_position.x * 3.0 - uniforms.time * 0.02,
+            object_position.y * 3.0 + uniforms.time * 0.035,
+            object_position.z * 3.0 + uniforms.time * 0.015,
+            6,
+            footprint * 3.0,
         );
         let nebula_density = nebula_gas1 * 0.6 + nebula_gas2 * 0.4;
         
         // === CAPA 2: REMOLINOS DE POLVO CÓSMICO ===
-        let cosmic_dust1 = fbm(
-            uv.0 * 8.0 + uniforms.time * 0.05,
-            uv.1 * 8.0 - uniforms.time * 0.04,
-            5
-        );
-        let cosmic_dust2 = fbm(
-            uv.0 * 12.0 - uniforms.time * 0.03,
-            uv.1 * 12.0 + uniforms.time * 0.06,
-            4
-        );
+        let cosmic_dust1 = triplanar(direction, 8.0, 4.0, |a, b| {
+            self.noise.fbm_filtered(a + uniforms.time * 0.05, b - uniforms.time * 0.04, 5, direction_footprint * 8.0)
+        });
+        let cosmic_dust2 = triplanar(direction, 12.0, 4.0, |a, b| {
+            self.noise.fbm_filtered(a - uniforms.time * 0.03, b + uniforms.time * 0.06, 4, direction_footprint * 12.0)
+        });
         let dust_swirls = cosmic_dust1 * 0.5 + cosmic_dust2 * 0.5;
         
         // === CAPA 3: CAMPOS DE IONIZACIÓN ===
-        let ionization1 = fbm3d(
-            position.x * 5.0 + uniforms.time * 0.1,
-            position.y * 5.0,
-            position.z * 5.0 - uniforms.time * 0.08,
-            4
+        let ionization1 = self.noise.fbm3d_filtered(
+            NoiseBasis::Value,
+            object_position.x * 5.0 + uniforms.time * 0.1,
+            object_position.y * 5.0,
+            object_position.z * 5.0 - uniforms.time * 0.08,
+            4,
+            footprint * 5.0,
         );
-        let ionization2 = fbm3d(
-            position.x * 7.0 - uniforms.time * 0.12,
-            position.y * 7.0 + uniforms.time * 0.09,
-            position.z * 7.0,
-            3
+        let ionization2 = self.noise.fbm3d_filtered(
+            NoiseBasis::Value,
+            object_position.x * 7.0 - uniforms.time * 0.12,
+            object_position.y * 7.0 + uniforms.time * 0.09,
+            object_position.z * 7.0,
+            3,
+            footprint * 7.0,
         );
         let ion_fields = ionization1 * 0.6 + ionization2 * 0.4;
         
         // === CAPA 4: VÓRTICES MAGNÉTICOS ===
-        let vortex_pattern = voronoi_noise(
-            uv.0 * 6.0 + uniforms.time * 0.04,
-            uv.1 * 6.0 - uniforms.time * 0.035
-        );
+        let vortex_pattern = self.noise.cellular3(
+            direction.x * 6.0 + uniforms.time * 0.04,
+            direction.y * 6.0 - uniforms.time * 0.035,
+            direction.z * 6.0,
+            DistanceMetric::Euclidean,
+        ).f1;
         let vortex_intensity = smoothstep(0.2, 0.1, vortex_pattern);
         
         // === CAPA 5: RAYOS CÓSMICOS Y RADIACIÓN ===
-        let cosmic_rays = ridge_noise(
-            uv.0 * 20.0 + uniforms.time * 0.3,
-            uv.1 * 20.0 - uniforms.time * 0.25,
-            3
-        );
+        let cosmic_rays = triplanar(direction, 20.0, 4.0, |a, b| {
+            self.noise.ridge_noise_filtered(a + uniforms.time * 0.3, b - uniforms.time * 0.25, 3, direction_footprint * 20.0)
+        });
         let ray_intensity = smoothstep(0.75, 0.9, cosmic_rays);
         
         // === CAPA 6: ESTRELLAS EN FORMACIÓN ===
-        let star_formation = voronoi_noise(uv.0 * 15.0, uv.1 * 15.0);
+        let star_formation = self.noise.cellular3(
+            direction.x * 15.0,
+            direction.y * 15.0,
+            direction.z * 15.0,
+            DistanceMetric::Euclidean,
+        ).f1;
         let proto_stars = smoothstep(0.05, 0.02, star_formation);
         let star_glow = smoothstep(0.12, 0.02, star_formation);
         
@@ -148,7 +176,8 @@ impl PlanetShader for LavaPlanetShader {
         
         // Estrellas en formación (muy brillantes)
         if proto_stars > 0.6 {
-            let star_pulse = (uniforms.time * 5.0 + uv.0 * 50.0).sin() * 0.5 + 0.5;
+            let (longitude, _) = spherical_uv(direction);
+            let star_pulse = (uniforms.time * 5.0 + longitude * std::f32::consts::TAU * 8.0).sin() * 0.5 + 0.5;
             let star_color = mix_color(yellow_star, white_hot, star_pulse);
             base_color = mix_color(base_color, star_color, proto_stars);
         }
@@ -169,9 +198,15 @@ impl PlanetShader for LavaPlanetShader {
         let volumetric_glow = nebula_density * 0.5 + dust_swirls * 0.3 + ion_fields * 0.2;
         
         // === CAPA 10: PARTÍCULAS ESTELARES ===
-        let particles = simple_noise(uv.0 * 100.0, uv.1 * 100.0);
+        // Un valor aleatorio por celda 3D: partículas fijas sobre la superficie
+        let particles = self.noise.cellular3(
+            direction.x * 100.0,
+            direction.y * 100.0,
+            direction.z * 100.0,
+            DistanceMetric::Euclidean,
+        ).cell_value();
         if particles > 0.98 {
-            let particle_brightness = simple_noise(uv.0 * 200.0 + uniforms.time, uv.1 * 200.0);
+            let particle_brightness = self.noise.simple_noise(particles * 200.0 + uniforms.time, particles * 200.0);
             let particle_color = mix_color(yellow_star, white_hot, particle_brightness);
             base_color = mix_color(base_color, particle_color, (particles - 0.98) * 50.0);
         }
@@ -227,3 +262,20 @@ impl PlanetShader for LavaPlanetShader {
         )
     }
 }
+
+pub fn descriptor() -> PlanetDescriptor {
+    PlanetDescriptor {
+        id: "nebula",
+        name: "Planeta Nebulosa",
+        description: "Gas cósmico etéreo",
+        icon: "🌌",
+        color: ShaderColor::from_rgb(200, 0, 150),
+        rotation_speed: 1.5,
+        scene_scale: 1.4,
+        axial_tilt: 1.71,
+        precession_rate: 0.02,
+        axis_longitude: 0.0,
+        default_params: ShaderParams::new().with("seed", 4.0),
+        factory: |params| Box::new(NebulaPlanetShader::new(params.seed())),
+    }
+}
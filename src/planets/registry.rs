@@ -0,0 +1,81 @@
+// ============================================================================
+// REGISTRO DE PLANETAS
+// Cada shader se registra bajo un id de texto junto con sus metadatos;
+// la interfaz y las teclas de selección se generan a partir de este registro
+// ============================================================================
+
+use crate::shaders::{PlanetShader, ShaderColor, ShaderParams};
+
+/// Función que construye un shader a partir de sus parámetros
+pub type ShaderFactory = fn(&ShaderParams) -> Box<dyn PlanetShader>;
+
+/// Metadatos y fábrica de un tipo de planeta
+pub struct PlanetDescriptor {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub description: &'static str,
+    pub icon: &'static str,
+    pub color: ShaderColor,
+    pub rotation_speed: f32,
+    /// Tamaño del cuerpo en la vista de sistema, relativo a un planeta de radio 0.5
+    pub scene_scale: f32,
+    /// Inclinación del eje de giro respecto a la vertical, en radianes
+    pub axial_tilt: f32,
+    /// Velocidad con la que el eje inclinado barre un cono (precesión), en rad/s
+    pub precession_rate: f32,
+    /// Hacia dónde se inclina el eje en t = 0 (ángulo inicial de la precesión), en radianes
+    pub axis_longitude: f32,
+    pub default_params: ShaderParams,
+    pub factory: ShaderFactory,
+}
+
+impl PlanetDescriptor {
+    /// Crea el shader con los parámetros indicados
+    pub fn create_shader(&self, params: &ShaderParams) -> Box<dyn PlanetShader> {
+        (self.factory)(params)
+    }
+}
+
+/// Colección ordenada de tipos de planeta disponibles
+#[derive(Default)]
+pub struct PlanetRegistry {
+    descriptors: Vec<PlanetDescriptor>,
+}
+
+impl PlanetRegistry {
+    /// Crea un registro vacío
+    pub fn new() -> Self {
+        PlanetRegistry { descriptors: Vec::new() }
+    }
+
+    /// Registra un nuevo tipo de planeta; el id debe ser único
+    pub fn register(&mut self, descriptor: PlanetDescriptor) -> Result<(), String> {
+        if self.index_of(descriptor.id).is_some() {
+            return Err(format!("Ya existe un planeta registrado con id '{}'", descriptor.id));
+        }
+        self.descriptors.push(descriptor);
+        Ok(())
+    }
+
+    /// Busca la posición de un planeta por su id
+    pub fn index_of(&self, id: &str) -> Option<usize> {
+        self.descriptors.iter().position(|descriptor| descriptor.id == id)
+    }
+
+    /// Obtiene un planeta por su posición en el registro
+    pub fn get_index(&self, index: usize) -> Option<&PlanetDescriptor> {
+        self.descriptors.get(index)
+    }
+
+    pub fn len(&self) -> usize {
+        self.descriptors.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.descriptors.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &PlanetDescriptor> {
+        self.descriptors.iter()
+    }
+}
@@ -4,143 +4,158 @@
 // ============================================================================
 
 use crate::vector::Vector3;
-use crate::shaders::{ShaderColor, ShaderUniforms, PlanetShader, fbm3d, voronoi_noise, fbm, ridge_noise, smoothstep, mix_color};
+use crate::sphere::PLANET_RADIUS;
+use crate::craters::CraterField;
+use crate::shaders::{ShaderColor, ShaderUniforms, ShaderParams, PlanetShader, NoiseSource, NoiseNode, NoiseNodeExt, BasisNode, CombineOp, ColorRamp, RampInterpolation, RampColorSpace, NoiseBasis, smoothstep, mix_color, triplanar, displaced_normal};
+use super::registry::PlanetDescriptor;
 
-pub struct RockyPlanetShader;
+/// Media separación angular entre vértices de `sphere.obj`: los cráteres más
+/// pequeños no caben en la malla y se desvanecen en el relieve
+const MESH_FOOTPRINT: f32 = 0.08;
 
-impl PlanetShader for RockyPlanetShader {
-    fn vertex_shader(&self, position: Vector3, normal: Vector3, _uv: (f32, f32), uniforms: &ShaderUniforms) -> (Vector3, Vector3) {
+pub struct RockyPlanetShader {
+    noise: NoiseSource,
+    /// Relieve estático: montañas, colinas en terrazas y rocas sueltas
+    terrain: Box<dyn NoiseNode>,
+    rock_ramp: ColorRamp,
+    craters: CraterField,
+}
+
+impl RockyPlanetShader {
+    pub fn new(seed: u32, crater_count: u32) -> Self {
+        // Paleta de 7 tonos de gris (no cambia con el tiempo)
+        let rock_ramp = ColorRamp::new(RampInterpolation::Smoothstep, RampColorSpace::Srgb)
+            .with_stop(0.0, ShaderColor::from_rgb(20, 20, 25))       // Gris casi negro
+            .with_stop(0.2, ShaderColor::from_rgb(50, 50, 55))       // Gris oscuro
+            .with_stop(0.4, ShaderColor::from_rgb(80, 80, 85))       // Gris medio-oscuro
+            .with_stop(0.6, ShaderColor::from_rgb(110, 110, 115))    // Gris medio
+            .with_stop(0.75, ShaderColor::from_rgb(140, 140, 145))   // Gris claro
+            .with_stop(0.85, ShaderColor::from_rgb(170, 170, 175))   // Gris más claro
+            .with_stop(1.0, ShaderColor::from_rgb(200, 200, 205));   // Gris casi blanco
+        
+        // Capa 1: Montañas grandes con crestas afiladas (ridged multifractal)
+        let mountains = BasisNode::new(seed.wrapping_add(1), NoiseBasis::Value)
+            .ridged(4, 2.0, 2.0, 1.0)
+            .bias(0.4)
+            .scale(Vector3::new(2.0, 2.0, 2.0))
+            .remap(0.45, 1.0, 0.0, 0.07);
+        
+        // Capa 2: Colinas medianas escalonadas como mesetas
+        let hills = BasisNode::new(seed.wrapping_add(2), NoiseBasis::Value)
+            .fbm(3, 2.0, 0.5)
+            .terrace(4.0, 0.5)
+            .scale(Vector3::new(5.0, 5.0, 5.0))
+            .remap(-1.0, 1.0, -0.08, 0.08);
+        
+        // Capa 3: Detalles finos (rocas pequeñas con pliegues de turbulencia)
+        let details = BasisNode::new(seed.wrapping_add(3), NoiseBasis::Value)
+            .turbulence(2, 2.0, 0.5)
+            .scale(Vector3::new(15.0, 15.0, 15.0))
+            .remap(0.0, 1.0, -0.012, 0.04);
+        
+        let terrain = mountains
+            .combine(hills, CombineOp::Add)
+            .combine(details, CombineOp::Add)
+            .boxed();
+        
+        RockyPlanetShader {
+            noise: NoiseSource::new(seed),
+            terrain,
+            rock_ramp,
+            // Muchos cráteres pequeños y pocos grandes (N(> r) ∝ r^-2)
+            craters: CraterField::new(seed.wrapping_add(5), crater_count, 0.06, 0.5, 2.0),
+        }
+    }
+
+    /// Desplazamiento del terreno a lo largo de la normal en un punto de la esfera
+    fn terrain_height(&self, position: Vector3, time: f32) -> f32 {
         // === DEFORMACIÓN PROCEDURAL DEL TERRENO ===
         
-        // Capa 1: Montañas grandes (escala global)
-        let mountain_noise = fbm3d(
-            position.x * 2.0,
-            position.y * 2.0,
-            position.z * 2.0,
-            4
-        ) * 0.15;
-        
-        // Capa 2: Colinas medianas
-        let hill_noise = fbm3d(
-            position.x * 5.0,
-            position.y * 5.0,
-            position.z * 5.0,
-            3
-        ) * 0.08;
-        
-        // Capa 3: Detalles finos (rocas pequeñas)
-        let detail_noise = fbm3d(
-            position.x * 15.0,
-            position.y * 15.0,
-            position.z * 15.0,
-            2
-        ) * 0.03;
-        
-        // Capa 4: Cráteres procedurales
-        let crater_x = position.x * 8.0;
-        let crater_y = position.y * 8.0;
-        let crater_pattern = voronoi_noise(crater_x, crater_y);
-        let crater_depth = if crater_pattern < 0.2 {
-            -0.05 * (1.0 - crater_pattern / 0.2)
-        } else {
-            0.0
-        };
+        // Capas 1-3: Montañas, colinas y detalles finos
+        let relief = self.terrain.eval(position);
+        
+        // Capa 4: Cráteres con cuenco, borde, pico central y manto de eyección
+        let crater_depth = self.craters.height(position.normalize(), MESH_FOOTPRINT) * PLANET_RADIUS;
         
         // Capa 5: Animación sutil (pulso tectónico)
-        let tectonic_pulse = (uniforms.time * 0.5).sin() * 0.01;
-        let pulse_noise = fbm3d(
-            position.x * 3.0 + uniforms.time * 0.1,
+        let tectonic_pulse = (time * 0.5).sin() * 0.01;
+        let pulse_noise = self.noise.fbm3d(
+            position.x * 3.0 + time * 0.1,
             position.y * 3.0,
             position.z * 3.0,
             2
         ) * tectonic_pulse;
         
         // Combinar todas las deformaciones
-        let total_displacement = mountain_noise + hill_noise + detail_noise + crater_depth + pulse_noise;
-        
+        relief + crater_depth + pulse_noise
+    }
+}
+
+impl PlanetShader for RockyPlanetShader {
+    fn vertex_shader(&self, position: Vector3, normal: Vector3, _uv: (f32, f32), uniforms: &ShaderUniforms) -> (Vector3, Vector3) {
         // Aplicar deformación a lo largo de la normal
-        let deformed_position = Vector3::new(
-            position.x + normal.x * total_displacement,
-            position.y + normal.y * total_displacement,
-            position.z + normal.z * total_displacement,
-        );
+        let total_displacement = self.terrain_height(position, uniforms.time);
+        let deformed_position = position + normal * total_displacement;
         
-        // Recalcular normal aproximada basada en deformación
-        let epsilon = 0.01;
-        let neighbor_noise = fbm3d(
-            (position.x + epsilon) * 2.0,
-            (position.y + epsilon) * 2.0,
-            (position.z + epsilon) * 2.0,
-            4
-        ) * 0.15;
-        
-        let tangent_displacement = neighbor_noise - mountain_noise;
-        let normal_perturbation = Vector3::new(
-            -tangent_displacement,
-            -tangent_displacement,
-            -tangent_displacement,
-        ) * 0.3;
-        
-        let perturbed_normal = Vector3::new(
-            normal.x + normal_perturbation.x,
-            normal.y + normal_perturbation.y,
-            normal.z + normal_perturbation.z,
-        ).normalize();
+        // Normal real del terreno deformado (diferencias centrales del mismo campo)
+        let perturbed_normal = displaced_normal(position, normal, 0.01, |p| self.terrain_height(p, uniforms.time));
         
         (deformed_position, perturbed_normal)
     }
 
-    fn fragment_shader(&self, position: Vector3, normal: Vector3, uv: (f32, f32), uniforms: &ShaderUniforms) -> ShaderColor {
-        // === PALETA DE COLORES GRISES (NO CAMBIA CON EL TIEMPO) ===
+    fn color_ramps_mut(&mut self) -> Vec<&mut ColorRamp> {
+        vec![&mut self.rock_ramp]
+    }
+
+    fn fragment_shader(&self, position: Vector3, normal: Vector3, _uv: (f32, f32), uniforms: &ShaderUniforms) -> ShaderColor {
+        // === TONOS EXTREMOS PARA CRÁTERES, FRACTURAS Y VETAS ===
         let darkest_gray = ShaderColor::from_rgb(20, 20, 25);      // Gris casi negro
         let dark_gray = ShaderColor::from_rgb(50, 50, 55);         // Gris oscuro
-        let medium_dark = ShaderColor::from_rgb(80, 80, 85);       // Gris medio-oscuro
-        let medium_gray = ShaderColor::from_rgb(110, 110, 115);    // Gris medio
-        let light_gray = ShaderColor::from_rgb(140, 140, 145);     // Gris claro
-        let lighter_gray = ShaderColor::from_rgb(170, 170, 175);   // Gris más claro
         let lightest_gray = ShaderColor::from_rgb(200, 200, 205);  // Gris casi blanco
         
+        // Muestrear en espacio de objeto para que la textura gire con el planeta
+        let object_position = uniforms.object_position(position);
+        let direction = object_position.normalize();
+        
+        // Huella del píxel en espacio de objeto y sobre la esfera unitaria
+        let footprint = uniforms.footprint(position);
+        let direction_footprint = footprint / object_position.length();
+        
         // === CAPA 1: TEXTURA BASE (Variación de rocas) ===
-        let rock_variation = fbm3d(
-            position.x * 8.0,
-            position.y * 8.0,
-            position.z * 8.0,
-            5
+        let rock_variation = self.noise.fbm3d_filtered(
+            NoiseBasis::Value,
+            object_position.x * 8.0,
+            object_position.y * 8.0,
+            object_position.z * 8.0,
+            5,
+            footprint * 8.0,
         );
         
         // === CAPA 2: DETALLES GEOLÓGICOS (Erosión y fracturas) ===
-        let erosion = fbm(uv.0 * 20.0, uv.1 * 20.0, 4);
-        let fractures = ridge_noise(uv.0 * 15.0, uv.1 * 15.0, 3);
+        let erosion = triplanar(direction, 20.0, 4.0, |a, b| {
+            self.noise.fbm_filtered(a, b, 4, direction_footprint * 20.0)
+        });
+        let fractures = triplanar(direction, 15.0, 4.0, |a, b| {
+            self.noise.ridge_noise_filtered(a, b, 3, direction_footprint * 15.0)
+        });
         
-        // === CAPA 3: CRÁTERES (Oscurecimiento) ===
-        let crater_noise = voronoi_noise(uv.0 * 8.0, uv.1 * 8.0);
-        let is_crater = crater_noise < 0.2;
+        // === CAPA 3: CRÁTERES (los mismos del relieve, como máscara de albedo) ===
+        let craters = self.craters.sample(direction, direction_footprint);
         
         // === CAPA 4: VETAS MINERALES (Líneas más claras) ===
-        let mineral_veins = ridge_noise(uv.0 * 25.0, uv.1 * 25.0, 2);
+        let mineral_veins = triplanar(direction, 25.0, 4.0, |a, b| {
+            self.noise.ridge_noise_filtered(a, b, 2, direction_footprint * 25.0)
+        });
         let has_veins = mineral_veins > 0.75;
         
         // === SELECCIÓN DE COLOR BASE (Solo grises, NO cambia) ===
-        let base_color = if rock_variation < 0.2 {
-            mix_color(darkest_gray, dark_gray, smoothstep(0.0, 0.2, rock_variation))
-        } else if rock_variation < 0.4 {
-            mix_color(dark_gray, medium_dark, smoothstep(0.2, 0.4, rock_variation))
-        } else if rock_variation < 0.6 {
-            mix_color(medium_dark, medium_gray, smoothstep(0.4, 0.6, rock_variation))
-        } else if rock_variation < 0.75 {
-            mix_color(medium_gray, light_gray, smoothstep(0.6, 0.75, rock_variation))
-        } else if rock_variation < 0.85 {
-            mix_color(light_gray, lighter_gray, smoothstep(0.75, 0.85, rock_variation))
-        } else {
-            mix_color(lighter_gray, lightest_gray, smoothstep(0.85, 1.0, rock_variation))
-        };
+        let base_color = self.rock_ramp.sample(rock_variation);
         
         let mut final_base = base_color;
         
-        // === APLICAR CRÁTERES (Oscurecer) ===
-        if is_crater {
-            final_base = mix_color(final_base, darkest_gray, 0.6);
-        }
+        // === APLICAR CRÁTERES (Oscurecer el fondo, aclarar los rayos recientes) ===
+        final_base = mix_color(final_base, darkest_gray, craters.interior * 0.6);
+        final_base = mix_color(final_base, lightest_gray, craters.ejecta * 0.5);
         
         // === APLICAR EROSIÓN (Variación sutil) ===
         if erosion > 0.6 {
@@ -193,3 +208,21 @@ impl PlanetShader for RockyPlanetShader {
         )
     }
 }
+
+pub fn descriptor() -> PlanetDescriptor {
+    PlanetDescriptor {
+        id: "rocky",
+        name: "Planeta Rocoso",
+        description: "Terreno deformado con cráteres",
+        icon: "🪨",
+        color: ShaderColor::from_rgb(160, 160, 160),
+        rotation_speed: 0.5,
+        scene_scale: 0.7,
+        axial_tilt: 0.03,
+        precession_rate: 0.0,
+        axis_longitude: 0.0,
+        default_params: ShaderParams::new().with("seed", 1.0).with("crater_count", 60.0),
+        factory: |params| Box::new(RockyPlanetShader::new(params.seed(), params.get("crater_count", 60.0).max(0.0) as u32)),
+    }
+}
+
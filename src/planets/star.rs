@@ -0,0 +1,125 @@
+// ============================================================================
+// PLANETA 7: ESTRELLA
+// Características: Color de cuerpo negro, oscurecimiento del limbo, granulación
+// convectiva animada, manchas solares a la deriva y corona
+// ============================================================================
+
+use crate::vector::Vector3;
+use crate::shaders::{ShaderColor, ShaderUniforms, ShaderParams, PlanetShader, HaloStyle, NoiseSource, NoiseBasis, DistanceMetric, smoothstep, mix, octave_fade, lat_lon, blackbody_color, limb_darkening};
+use super::registry::PlanetDescriptor;
+
+/// Escala de las celdas de granulación sobre la esfera unitaria
+const GRANULE_SCALE: f32 = 40.0;
+
+pub struct StarShader {
+    noise: NoiseSource,
+    temperature: f32,
+}
+
+impl StarShader {
+    pub fn new(seed: u32, temperature: f32) -> Self {
+        StarShader {
+            noise: NoiseSource::new(seed),
+            temperature,
+        }
+    }
+}
+
+impl PlanetShader for StarShader {
+    fn vertex_shader(&self, position: Vector3, normal: Vector3, _uv: (f32, f32), _uniforms: &ShaderUniforms) -> (Vector3, Vector3) {
+        (position, normal)
+    }
+
+    fn fragment_shader(&self, position: Vector3, normal: Vector3, _uv: (f32, f32), uniforms: &ShaderUniforms) -> ShaderColor {
+        let object_position = uniforms.object_position(position);
+        let direction = object_position.normalize();
+        let footprint = uniforms.footprint(position) / object_position.length();
+        let view_dir = (uniforms.camera_position - position).normalize();
+        let mu = normal.dot(&view_dir).max(0.0);
+
+        // === OSCURECIMIENTO DEL LIMBO ===
+        // Coeficientes cuadráticos aproximados del Sol en el visible
+        let limb = limb_darkening(mu, 0.47, 0.23);
+
+        // === GRANULACIÓN ===
+        // Celdas de convección: centros calientes y bordes oscuros; el tiempo es
+        // la cuarta coordenada, así las celdas nacen y mueren sin deslizarse
+        let granule = self.noise.cellular4(
+            direction.x * GRANULE_SCALE,
+            direction.y * GRANULE_SCALE,
+            direction.z * GRANULE_SCALE,
+            uniforms.time * 0.15,
+            DistanceMetric::Euclidean,
+        );
+        let lanes = smoothstep(0.02, 0.15, granule.edge_distance());
+        let hot_center = 1.0 - smoothstep(0.0, 0.7, granule.f1);
+        let granulation_detail = mix(0.78, 1.05, lanes * 0.6 + hot_center * 0.4);
+        // De lejos la granulación es más fina que un píxel: se promedia
+        let granulation = mix(0.95, granulation_detail, octave_fade(GRANULE_SCALE, footprint));
+
+        // === MANCHAS SOLARES ===
+        // Rotación diferencial: el ecuador gira más rápido que las latitudes altas
+        let (latitude, _) = lat_lon(direction);
+        let drift = uniforms.time * 0.05 * (1.0 - 0.2 * latitude.sin().powi(2));
+        let (sin_drift, cos_drift) = drift.sin_cos();
+        let drifted = Vector3::new(
+            direction.x * cos_drift - direction.z * sin_drift,
+            direction.y,
+            direction.x * sin_drift + direction.z * cos_drift,
+        );
+        let spot_field = self.noise.fbm3d_basis(
+            NoiseBasis::Simplex,
+            drifted.x * 3.0,
+            drifted.y * 3.0,
+            drifted.z * 3.0,
+            3,
+        );
+        // Las manchas aparecen en bandas de actividad entre ~5° y ~35° de latitud
+        let active_band = smoothstep(0.05, 0.15, latitude.abs()) * smoothstep(0.65, 0.45, latitude.abs());
+        let spot = spot_field * active_band;
+        let umbra = smoothstep(0.38, 0.45, spot);
+        let penumbra = smoothstep(0.28, 0.36, spot);
+        let spot_darkening = 1.0 - penumbra * 0.35 - umbra * 0.45;
+
+        // === COLOR DE CUERPO NEGRO ===
+        // Hacia el limbo se ven capas más altas y frías; las manchas también son más frías
+        let local_temperature = self.temperature * (0.85 + 0.15 * mu) * (1.0 - penumbra * 0.1 - umbra * 0.25);
+        let color = blackbody_color(local_temperature);
+
+        // Emisión pura: la estrella no depende de light_direction
+        let intensity = limb * granulation * spot_darkening * 1.3;
+
+        ShaderColor::new(
+            (color.r * intensity).clamp(0.0, 1.0),
+            (color.g * intensity).clamp(0.0, 1.0),
+            (color.b * intensity).clamp(0.0, 1.0),
+            1.0,
+        )
+    }
+
+    fn halo(&self, _uniforms: &ShaderUniforms) -> Option<HaloStyle> {
+        let corona = blackbody_color(self.temperature * 1.1);
+        Some(HaloStyle {
+            color: ShaderColor::new(corona.r, corona.g, corona.b, 0.6),
+            extent: 1.8,
+            streamers: 0.35,
+        })
+    }
+}
+
+pub fn descriptor() -> PlanetDescriptor {
+    PlanetDescriptor {
+        id: "star",
+        name: "Estrella",
+        description: "Sol con granulación, manchas y corona",
+        icon: "☀️",
+        color: ShaderColor::from_rgb(255, 210, 120),
+        rotation_speed: 0.15,
+        scene_scale: 3.0,
+        axial_tilt: 0.13,
+        precession_rate: 0.0,
+        axis_longitude: 0.0,
+        default_params: ShaderParams::new().with("seed", 7.0).with("temperature", 5800.0),
+        factory: |params| Box::new(StarShader::new(params.seed(), params.get("temperature", 5800.0))),
+    }
+}
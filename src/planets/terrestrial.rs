@@ -0,0 +1,353 @@
+// ============================================================================
+// PLANETA 6: PLANETA TERRESTRE CON BIOMAS
+// Características: Océanos con glint solar, playas, praderas, bosques, desiertos,
+// tundra y casquetes polares elegidos por elevación, temperatura y humedad.
+// Luces de ciudades en el lado nocturno
+// ============================================================================
+
+use crate::vector::Vector3;
+use crate::shaders::{ShaderColor, ShaderUniforms, ShaderParams, PlanetShader, RenderLayer, NoiseNode, NoiseNodeExt, BasisNode, NoiseBasis, NoiseSource, DistanceMetric, ColorRamp, RampInterpolation, RampColorSpace, smoothstep, mix, mix_color, displaced_normal, lat_lon, octave_fade, night_mask};
+use crate::sphere::PLANET_RADIUS;
+use crate::atmosphere::Atmosphere;
+use crate::clouds::CloudShader;
+use crate::aurora::AuroraShader;
+use super::registry::PlanetDescriptor;
+
+/// Altura máxima del relieve sobre el nivel del mar (en unidades del objeto)
+const LAND_RELIEF: f32 = 0.06;
+
+/// Radio de la capa de nubes respecto a la superficie: justo por encima de
+/// los picos más altos, para que ninguno atraviese las nubes que le dan sombra
+const CLOUD_RADIUS_SCALE: f32 = 1.0 + LAND_RELIEF / PLANET_RADIUS + 0.01;
+
+/// Velocidad de giro del suelo; las capas que lo acompañan usan la misma
+const ROTATION_SPEED: f32 = 0.4;
+
+/// Frecuencia de las aglomeraciones urbanas y de las luces sueltas dentro de ellas
+const CITY_SCALE: f32 = 14.0;
+const STREET_SCALE: f32 = 90.0;
+
+/// Clima en un punto de la esfera
+struct ClimateSample {
+    elevation: f32,
+    moisture: f32,
+    /// Altura sobre el nivel del mar en [0, 1]
+    height: f32,
+    temperature: f32,
+}
+
+/// Tipos de bioma terrestre
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Biome {
+    Beach,
+    Grassland,
+    Forest,
+    Desert,
+    Tundra,
+    Snow,
+}
+
+impl Biome {
+    /// Clasifica un punto de tierra. `height` es la altura sobre el mar en [0, 1],
+    /// `temperature` y `moisture` están en [0, 1]
+    pub fn classify(height: f32, temperature: f32, moisture: f32) -> Biome {
+        if temperature < 0.15 {
+            Biome::Snow
+        } else if temperature < 0.3 {
+            Biome::Tundra
+        } else if height < 0.04 {
+            Biome::Beach
+        } else if moisture < 0.3 {
+            Biome::Desert
+        } else if moisture < 0.6 {
+            Biome::Grassland
+        } else {
+            Biome::Forest
+        }
+    }
+
+    pub fn color(&self) -> ShaderColor {
+        match self {
+            Biome::Beach => ShaderColor::from_rgb(220, 205, 150),      // Arena
+            Biome::Grassland => ShaderColor::from_rgb(110, 160, 60),   // Pradera
+            Biome::Forest => ShaderColor::from_rgb(35, 95, 40),        // Bosque
+            Biome::Desert => ShaderColor::from_rgb(210, 170, 100),     // Desierto
+            Biome::Tundra => ShaderColor::from_rgb(130, 135, 110),     // Tundra
+            Biome::Snow => ShaderColor::from_rgb(240, 245, 250),       // Nieve
+        }
+    }
+}
+
+pub struct TerrestrialPlanetShader {
+    elevation: Box<dyn NoiseNode>,
+    moisture: Box<dyn NoiseNode>,
+    sea_level: f32,
+    ocean_ramp: ColorRamp,
+    cities: NoiseSource,
+    atmosphere: Option<Atmosphere>,
+    layers: Vec<RenderLayer>,
+}
+
+impl TerrestrialPlanetShader {
+    pub fn new(seed: u32, sea_level: f32) -> Self {
+        // Continentes amplios con costas recortadas. Rango [-1, 1]
+        let elevation = BasisNode::new(seed, NoiseBasis::Simplex)
+            .fbm(6, 2.0, 0.5)
+            .scale(Vector3::new(2.5, 2.5, 2.5))
+            .remap(-0.5, 0.5, -1.0, 1.0)
+            .clamp(-1.0, 1.0)
+            .boxed();
+
+        // Humedad independiente de la elevación. Rango [0, 1]; el gain separa
+        // más los desiertos de las selvas y acorta la franja de transición
+        let moisture = BasisNode::new(seed.wrapping_add(7), NoiseBasis::Perlin)
+            .fbm(4, 2.0, 0.5)
+            .scale(Vector3::new(3.0, 3.0, 3.0))
+            .remap(-0.4, 0.4, 0.0, 1.0)
+            .clamp(0.0, 1.0)
+            .gain(0.7)
+            .boxed();
+
+        // Profundidad del océano: 0 en la costa, 1 en el fondo abisal
+        let ocean_ramp = ColorRamp::new(RampInterpolation::Smoothstep, RampColorSpace::Oklab)
+            .with_stop(0.0, ShaderColor::from_rgb(70, 170, 180))       // Bajíos turquesa
+            .with_stop(0.15, ShaderColor::from_rgb(30, 110, 170))      // Plataforma
+            .with_stop(0.5, ShaderColor::from_rgb(15, 60, 130))        // Océano
+            .with_stop(1.0, ShaderColor::from_rgb(5, 20, 60));         // Fosa profunda
+
+        TerrestrialPlanetShader {
+            elevation,
+            moisture,
+            sea_level,
+            ocean_ramp,
+            cities: NoiseSource::new(seed.wrapping_add(13)),
+            atmosphere: None,
+            layers: Vec::new(),
+        }
+    }
+
+    /// Añade una capa (nubes...) por encima de las anteriores
+    pub fn with_layer(mut self, layer: RenderLayer) -> Self {
+        self.layers.push(layer);
+        self
+    }
+
+    /// Añade la atmósfera como capa exterior; también tiñe la luz del sol en la superficie
+    pub fn with_atmosphere(mut self, atmosphere: Atmosphere) -> Self {
+        self.layers.push(atmosphere.layer());
+        self.atmosphere = Some(atmosphere);
+        self
+    }
+
+    /// Altura sobre el nivel del mar en [0, 1] (0 en el océano)
+    fn land_height(&self, elevation: f32) -> f32 {
+        ((elevation - self.sea_level) / (1.0 - self.sea_level).max(0.01)).clamp(0.0, 1.0)
+    }
+
+    fn climate(&self, direction: Vector3, footprint: f32) -> ClimateSample {
+        let elevation = self.elevation.eval_filtered(direction, footprint);
+        let moisture = self.moisture.eval_filtered(direction, footprint);
+        let height = self.land_height(elevation);
+
+        // Fría en los polos y en la altura
+        let (latitude, _) = lat_lon(direction);
+        let polar = latitude.abs() / std::f32::consts::FRAC_PI_2;
+        let temperature = (1.0 - polar * 1.1 - height * 0.6 + (moisture - 0.5) * 0.1).clamp(0.0, 1.0);
+
+        ClimateSample { elevation, moisture, height, temperature }
+    }
+
+    /// Densidad de luces urbanas en [0, 1]: ciudades agrupadas en tierras
+    /// templadas, húmedas y bajas, sobre todo cerca de la costa
+    fn city_lights(&self, direction: Vector3, footprint: f32, climate: &ClimateSample) -> f32 {
+        if climate.elevation < self.sea_level {
+            return 0.0;
+        }
+        let habitable = smoothstep(0.25, 0.4, climate.temperature)
+            * smoothstep(0.1, 0.3, climate.moisture)
+            * smoothstep(0.8, 0.3, climate.height);
+        if habitable <= 0.0 {
+            return 0.0;
+        }
+        let coast = 0.6 + smoothstep(0.12, 0.0, climate.height) * 0.4;
+
+        // Aglomeraciones: una ciudad de tamaño aleatorio en algunas celdas
+        let p = direction * CITY_SCALE;
+        let metro = self.cities.cellular3(p.x, p.y, p.z, DistanceMetric::Euclidean);
+        let size = 0.3 + metro.cell_value() * 0.35;
+        let populated = smoothstep(0.25, 0.4, metro.cell_value());
+        let cluster = smoothstep(size, size * 0.3, metro.f1) * populated;
+
+        // Puntos de luz sueltos; sin resolución se sustituyen por su media
+        let q = direction * STREET_SCALE;
+        let dots = smoothstep(0.35, 0.1, self.cities.cellular3(q.x, q.y, q.z, DistanceMetric::Euclidean).f1);
+        let dots = mix(0.3, dots, octave_fade(STREET_SCALE, footprint));
+
+        (cluster * (0.6 + dots * 0.8) * habitable * coast).clamp(0.0, 1.0)
+    }
+
+    /// Resplandor de las ciudades, solo en el lado nocturno
+    fn night_lights(&self, direction: Vector3, footprint: f32, climate: &ClimateSample, night: f32) -> ShaderColor {
+        if night <= 0.0 {
+            return ShaderColor::BLACK;
+        }
+        let sodium = ShaderColor::from_rgb(255, 190, 100);         // Alumbrado de sodio
+        let glow = self.city_lights(direction, footprint, climate) * night * 1.5;
+        ShaderColor::new(sodium.r * glow, sodium.g * glow, sodium.b * glow, 1.0)
+    }
+
+    /// Desplazamiento radial: la tierra se eleva y el océano queda plano
+    fn surface_displacement(&self, direction: Vector3) -> f32 {
+        self.land_height(self.elevation.eval(direction.normalize())) * LAND_RELIEF
+    }
+}
+
+impl PlanetShader for TerrestrialPlanetShader {
+    fn vertex_shader(&self, position: Vector3, normal: Vector3, _uv: (f32, f32), _uniforms: &ShaderUniforms) -> (Vector3, Vector3) {
+        let displaced_position = position + normal * self.surface_displacement(position);
+        let terrain_normal = displaced_normal(position, normal, 0.01, |p| self.surface_displacement(p));
+        (displaced_position, terrain_normal)
+    }
+
+    fn fragment_shader(&self, position: Vector3, normal: Vector3, uv: (f32, f32), uniforms: &ShaderUniforms) -> ShaderColor {
+        self.shade(position, normal, uv, uniforms).0
+    }
+
+    /// El clima y las luces de ciudades se calculan una vez y sirven de color y de emisión
+    fn shade(&self, position: Vector3, normal: Vector3, _uv: (f32, f32), uniforms: &ShaderUniforms) -> (ShaderColor, ShaderColor) {
+        let object_position = uniforms.object_position(position);
+        let direction = object_position.normalize();
+        let footprint = uniforms.footprint(position) / object_position.length();
+
+        let climate = self.climate(direction, footprint);
+        let ClimateSample { elevation, moisture, height, temperature } = climate;
+        let is_ocean = elevation < self.sea_level;
+
+        // === COLOR BASE POR BIOMA ===
+        let base_color = if is_ocean {
+            let depth = ((self.sea_level - elevation) / (self.sea_level + 1.0).max(0.01)).clamp(0.0, 1.0);
+            let water = self.ocean_ramp.sample(depth);
+            // Hielo marino en los polos
+            mix_color(water, Biome::Snow.color(), smoothstep(0.12, 0.05, temperature))
+        } else {
+            let biome = Biome::classify(height, temperature, moisture);
+            // Ligera variación dentro del bioma según la humedad
+            let shade = 0.9 + moisture * 0.2;
+            let color = biome.color();
+            ShaderColor::new(color.r * shade, color.g * shade, color.b * shade, 1.0)
+        };
+
+        // === ILUMINACIÓN ===
+        let light_dir = uniforms.light_direction.normalize();
+        let view_dir = (uniforms.camera_position - position).normalize();
+        let diffuse = normal.dot(&light_dir).max(0.0);
+        let ambient = 0.12;
+        // La luz que atraviesa la atmósfera se enrojece cerca del terminador
+        let sun = self.atmosphere.as_ref().map_or(ShaderColor::WHITE, |atmosphere| atmosphere.sunlight(position, light_dir));
+
+        let mut final_color = ShaderColor::new(
+            base_color.r * (ambient + diffuse * 0.9 * sun.r),
+            base_color.g * (ambient + diffuse * 0.9 * sun.g),
+            base_color.b * (ambient + diffuse * 0.9 * sun.b),
+            1.0,
+        );
+
+        // Reflejo especular del sol solo sobre el agua abierta
+        if is_ocean && temperature > 0.12 {
+            let half_dir = (light_dir + view_dir).normalize();
+            let glint = normal.dot(&half_dir).max(0.0).powf(80.0) * diffuse.min(1.0);
+            let glint_color = ShaderColor::new(sun.r, sun.g * 0.98, sun.b * 0.92, 1.0);
+            final_color = mix_color(final_color, glint_color, glint.min(1.0));
+        }
+
+        // === LUCES DE CIUDADES: aparecen al cruzar el terminador ===
+        let lights = self.night_lights(direction, footprint, &climate, night_mask(normal, light_dir));
+        final_color = ShaderColor::new(
+            final_color.r + lights.r,
+            final_color.g + lights.g,
+            final_color.b + lights.b,
+            1.0,
+        );
+
+        let color = ShaderColor::new(
+            final_color.r.clamp(0.0, 1.0),
+            final_color.g.clamp(0.0, 1.0),
+            final_color.b.clamp(0.0, 1.0),
+            1.0,
+        );
+        (color, lights)
+    }
+
+    fn emission(&self, position: Vector3, normal: Vector3, uniforms: &ShaderUniforms) -> ShaderColor {
+        let object_position = uniforms.object_position(position);
+        let direction = object_position.normalize();
+        let footprint = uniforms.footprint(position) / object_position.length();
+        let night = night_mask(normal, uniforms.light_direction.normalize());
+        if night <= 0.0 {
+            return ShaderColor::BLACK;
+        }
+        let climate = self.climate(direction, footprint);
+        self.night_lights(direction, footprint, &climate, night)
+    }
+
+    fn layers(&self) -> &[RenderLayer] {
+        &self.layers
+    }
+
+    fn color_ramps_mut(&mut self) -> Vec<&mut ColorRamp> {
+        vec![&mut self.ocean_ramp]
+    }
+}
+
+pub fn descriptor() -> PlanetDescriptor {
+    PlanetDescriptor {
+        id: "terrestrial",
+        name: "Planeta Terrestre",
+        description: "Océanos, continentes y biomas",
+        icon: "🌎",
+        color: ShaderColor::from_rgb(60, 130, 200),
+        rotation_speed: ROTATION_SPEED,
+        scene_scale: 1.0,
+        axial_tilt: 0.41,
+        precession_rate: 0.05,
+        axis_longitude: 0.0,
+        default_params: ShaderParams::new()
+            .with("seed", 6.0)
+            .with("sea_level", 0.0)
+            .with("cloud_coverage", 0.45)
+            .with("cloud_speed", 0.55)
+            .with("atmosphere_density", 1.0)
+            .with("atmosphere_thickness", 0.15)
+            .with("scale_height", 0.25)
+            .with("wavelength_r", 680.0)
+            .with("wavelength_g", 550.0)
+            .with("wavelength_b", 440.0)
+            .with("aurora_activity", 0.6)
+            .with("magnetic_tilt", 0.2),
+        factory: |params| {
+            let atmosphere = Atmosphere::new(
+                params.get("atmosphere_thickness", 0.15),
+                params.get("scale_height", 0.25),
+                [
+                    params.get("wavelength_r", 680.0),
+                    params.get("wavelength_g", 550.0),
+                    params.get("wavelength_b", 440.0),
+                ],
+                params.get("atmosphere_density", 1.0),
+            );
+            // Las nubes giran algo más rápido que el suelo y se desplazan sobre él
+            let clouds = CloudShader::new(params.seed().wrapping_add(11), params.get("cloud_coverage", 0.45))
+                .layer(CLOUD_RADIUS_SCALE, params.get("cloud_speed", 0.55));
+            let mut shader = TerrestrialPlanetShader::new(params.seed(), params.get("sea_level", 0.0))
+                .with_layer(clouds);
+
+            // Aurora opcional: con actividad 0 no se añade la capa
+            let aurora_activity = params.get("aurora_activity", 0.6);
+            if aurora_activity > 0.0 {
+                let aurora = AuroraShader::new(params.seed().wrapping_add(17), params.get("magnetic_tilt", 0.2), aurora_activity);
+                shader = shader.with_layer(aurora.layer(&atmosphere, ROTATION_SPEED));
+            }
+            Box::new(shader.with_atmosphere(atmosphere))
+        },
+    }
+}
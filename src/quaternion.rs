@@ -0,0 +1,276 @@
+// ============================================================================
+// CUATERNIONES DE ROTACIÓN
+// Orientaciones sin bloqueo de cardán: construcción desde eje y ángulo, desde
+// ángulos de Euler o desde una matriz, composición, interpolación esférica
+// (slerp) y conversión a `Transform4x4` para el resto del pipeline
+// ============================================================================
+
+use crate::vector::Vector3;
+use crate::transform::Transform4x4;
+
+/// Cuaternión w + xi + yj + zk. Las rotaciones usan cuaterniones unitarios
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Quaternion {
+    pub w: f32,
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}
+
+impl Quaternion {
+    pub fn new(w: f32, x: f32, y: f32, z: f32) -> Self {
+        Quaternion { w, x, y, z }
+    }
+
+    /// Rotación nula
+    pub fn identity() -> Self {
+        Quaternion::new(1.0, 0.0, 0.0, 0.0)
+    }
+
+    /// Giro de `radians` alrededor de `axis` (regla de la mano derecha)
+    pub fn from_axis_angle(axis: Vector3, radians: f32) -> Self {
+        let axis = axis.normalize();
+        let (sine, cosine) = (radians * 0.5).sin_cos();
+        Quaternion::new(cosine, axis.x * sine, axis.y * sine, axis.z * sine)
+    }
+
+    /// Eje y ángulo de la rotación; el eje es +Y si el giro es nulo
+    #[allow(dead_code)]
+    pub fn to_axis_angle(self) -> (Vector3, f32) {
+        let q = self.normalize();
+        let angle = 2.0 * q.w.clamp(-1.0, 1.0).acos();
+        let sine = (1.0 - q.w * q.w).max(0.0).sqrt();
+        if sine < 1e-6 {
+            return (Vector3::new(0.0, 1.0, 0.0), 0.0);
+        }
+        (Vector3::new(q.x / sine, q.y / sine, q.z / sine), angle)
+    }
+
+    /// Ángulos de Euler intrínsecos en orden Y-X-Z: primero guiñada (`yaw`,
+    /// alrededor de Y, el eje vertical), luego cabeceo (`pitch`, X) y por
+    /// último alabeo (`roll`, Z). Equivale a Ry · Rx · Rz
+    pub fn from_euler(yaw: f32, pitch: f32, roll: f32) -> Self {
+        Quaternion::from_axis_angle(Vector3::new(0.0, 1.0, 0.0), yaw)
+            * Quaternion::from_axis_angle(Vector3::new(1.0, 0.0, 0.0), pitch)
+            * Quaternion::from_axis_angle(Vector3::new(0.0, 0.0, 1.0), roll)
+    }
+
+    /// Inversa de `from_euler`: (guiñada, cabeceo, alabeo). Con el cabeceo en
+    /// ±90° la guiñada y el alabeo se confunden y todo el giro va a la guiñada
+    #[allow(dead_code)]
+    pub fn to_euler(self) -> (f32, f32, f32) {
+        let m = self.to_matrix().elements;
+        let pitch = (-m[1][2]).clamp(-1.0, 1.0).asin();
+        if m[1][2].abs() < 0.9999 {
+            (m[0][2].atan2(m[2][2]), pitch, m[1][0].atan2(m[1][1]))
+        } else {
+            ((-m[2][0]).atan2(m[0][0]), pitch, 0.0)
+        }
+    }
+
+    /// Orientación de la parte de rotación de una matriz (sin escala)
+    #[allow(dead_code)]
+    pub fn from_rotation_matrix(matrix: &Transform4x4) -> Self {
+        let m = matrix.elements;
+        let trace = m[0][0] + m[1][1] + m[2][2];
+
+        // Se parte de la componente mayor para no dividir entre casi cero
+        let q = if trace > 0.0 {
+            let s = (trace + 1.0).sqrt() * 2.0;
+            Quaternion::new(0.25 * s, (m[2][1] - m[1][2]) / s, (m[0][2] - m[2][0]) / s, (m[1][0] - m[0][1]) / s)
+        } else if m[0][0] > m[1][1] && m[0][0] > m[2][2] {
+            let s = (1.0 + m[0][0] - m[1][1] - m[2][2]).sqrt() * 2.0;
+            Quaternion::new((m[2][1] - m[1][2]) / s, 0.25 * s, (m[0][1] + m[1][0]) / s, (m[0][2] + m[2][0]) / s)
+        } else if m[1][1] > m[2][2] {
+            let s = (1.0 + m[1][1] - m[0][0] - m[2][2]).sqrt() * 2.0;
+            Quaternion::new((m[0][2] - m[2][0]) / s, (m[0][1] + m[1][0]) / s, 0.25 * s, (m[1][2] + m[2][1]) / s)
+        } else {
+            let s = (1.0 + m[2][2] - m[0][0] - m[1][1]).sqrt() * 2.0;
+            Quaternion::new((m[1][0] - m[0][1]) / s, (m[0][2] + m[2][0]) / s, (m[1][2] + m[2][1]) / s, 0.25 * s)
+        };
+        q.normalize()
+    }
+
+    pub fn dot(&self, other: &Quaternion) -> f32 {
+        self.w * other.w + self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    pub fn length(&self) -> f32 {
+        self.dot(self).sqrt()
+    }
+
+    pub fn normalize(&self) -> Self {
+        let length = self.length();
+        if length == 0.0 {
+            return Quaternion::identity();
+        }
+        Quaternion::new(self.w / length, self.x / length, self.y / length, self.z / length)
+    }
+
+    /// Interpolación esférica a velocidad angular constante entre `self` (t = 0)
+    /// y `other` (t = 1), por el camino más corto
+    #[allow(dead_code)]
+    pub fn slerp(&self, other: &Quaternion, t: f32) -> Self {
+        let mut other = *other;
+        let mut cosine = self.dot(&other);
+        // q y -q son la misma rotación: elegir el hemisferio cercano
+        if cosine < 0.0 {
+            other = Quaternion::new(-other.w, -other.x, -other.y, -other.z);
+            cosine = -cosine;
+        }
+
+        let (from, to) = if cosine > 0.9995 {
+            // Casi iguales: la interpolación lineal es estable y basta
+            (1.0 - t, t)
+        } else {
+            let angle = cosine.acos();
+            let sine = angle.sin();
+            (((1.0 - t) * angle).sin() / sine, (t * angle).sin() / sine)
+        };
+
+        Quaternion::new(
+            self.w * from + other.w * to,
+            self.x * from + other.x * to,
+            self.y * from + other.y * to,
+            self.z * from + other.z * to,
+        )
+        .normalize()
+    }
+
+    /// Matriz de rotación equivalente
+    pub fn to_matrix(self) -> Transform4x4 {
+        let Quaternion { w, x, y, z } = self.normalize();
+        Transform4x4::from_elements(
+            1.0 - 2.0 * (y * y + z * z), 2.0 * (x * y - w * z), 2.0 * (x * z + w * y), 0.0,
+            2.0 * (x * y + w * z), 1.0 - 2.0 * (x * x + z * z), 2.0 * (y * z - w * x), 0.0,
+            2.0 * (x * z - w * y), 2.0 * (y * z + w * x), 1.0 - 2.0 * (x * x + y * y), 0.0,
+            0.0, 0.0, 0.0, 1.0,
+        )
+    }
+}
+
+/// Composición: `a * b` aplica primero `b` y luego `a`, como las matrices
+impl std::ops::Mul for Quaternion {
+    type Output = Quaternion;
+
+    fn mul(self, other: Quaternion) -> Quaternion {
+        Quaternion::new(
+            self.w * other.w - self.x * other.x - self.y * other.y - self.z * other.z,
+            self.w * other.x + self.x * other.w + self.y * other.z - self.z * other.y,
+            self.w * other.y - self.x * other.z + self.y * other.w + self.z * other.x,
+            self.w * other.z + self.x * other.y - self.y * other.x + self.z * other.w,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transform::build_y_axis_rotation;
+    use std::f32::consts::{FRAC_PI_2, FRAC_PI_4, PI};
+
+    const EPSILON: f32 = 1e-4;
+
+    /// Misma rotación: q y -q también lo son
+    fn assert_same_rotation(a: Quaternion, b: Quaternion) {
+        assert!(a.dot(&b).abs() > 1.0 - EPSILON, "{:?} != {:?}", a, b);
+    }
+
+    fn assert_same_matrix(a: &Transform4x4, b: &Transform4x4) {
+        for row in 0..4 {
+            for column in 0..4 {
+                let (x, y) = (a.elements[row][column], b.elements[row][column]);
+                assert!((x - y).abs() < EPSILON, "[{}][{}]: {} != {}", row, column, x, y);
+            }
+        }
+    }
+
+    #[test]
+    fn axis_angle_round_trip() {
+        let axis = Vector3::new(1.0, -2.0, 0.5).normalize();
+        let (recovered_axis, angle) = Quaternion::from_axis_angle(axis, 1.2).to_axis_angle();
+        assert!((angle - 1.2).abs() < EPSILON);
+        assert!((recovered_axis - axis).length() < EPSILON);
+
+        let (identity_axis, identity_angle) = Quaternion::identity().to_axis_angle();
+        assert_eq!(identity_angle, 0.0);
+        assert!((identity_axis - Vector3::new(0.0, 1.0, 0.0)).length() < EPSILON);
+    }
+
+    #[test]
+    fn axis_angle_matches_matrix_rotation() {
+        let quaternion = Quaternion::from_axis_angle(Vector3::new(0.0, 1.0, 0.0), 0.7);
+        assert_same_matrix(&quaternion.to_matrix(), &build_y_axis_rotation(0.7));
+    }
+
+    #[test]
+    fn euler_round_trip() {
+        for &(yaw, pitch, roll) in &[(0.3, -0.4, 1.1), (-2.5, 1.2, -0.2), (3.0, 0.0, -3.0), (0.0, -1.5, 0.0)] {
+            let (y, p, r) = Quaternion::from_euler(yaw, pitch, roll).to_euler();
+            assert!((y - yaw).abs() < EPSILON && (p - pitch).abs() < EPSILON && (r - roll).abs() < EPSILON,
+                "({}, {}, {}) -> ({}, {}, {})", yaw, pitch, roll, y, p, r);
+        }
+    }
+
+    #[test]
+    fn euler_at_gimbal_lock_keeps_the_rotation() {
+        let quaternion = Quaternion::from_euler(0.4, FRAC_PI_2, 0.3);
+        let (yaw, pitch, roll) = quaternion.to_euler();
+        assert!((pitch - FRAC_PI_2).abs() < 1e-2);
+        assert_eq!(roll, 0.0);
+        assert_same_rotation(Quaternion::from_euler(yaw, pitch, roll), quaternion);
+    }
+
+    #[test]
+    fn euler_order_is_yaw_pitch_roll() {
+        let composed = Quaternion::from_axis_angle(Vector3::new(0.0, 1.0, 0.0), 0.5)
+            * Quaternion::from_axis_angle(Vector3::new(1.0, 0.0, 0.0), -0.3)
+            * Quaternion::from_axis_angle(Vector3::new(0.0, 0.0, 1.0), 0.9);
+        assert_same_rotation(Quaternion::from_euler(0.5, -0.3, 0.9), composed);
+    }
+
+    #[test]
+    fn matrix_round_trip() {
+        // Incluye giros de casi media vuelta, donde la traza es negativa
+        let cases = [
+            Quaternion::from_axis_angle(Vector3::new(1.0, 1.0, 1.0), 0.8),
+            Quaternion::from_axis_angle(Vector3::new(1.0, 0.0, 0.0), PI - 0.01),
+            Quaternion::from_axis_angle(Vector3::new(0.0, 1.0, 0.0), PI - 0.01),
+            Quaternion::from_axis_angle(Vector3::new(0.0, 0.0, 1.0), PI - 0.01),
+            Quaternion::from_euler(2.0, -1.0, 2.5),
+        ];
+        for quaternion in cases {
+            assert_same_rotation(Quaternion::from_rotation_matrix(&quaternion.to_matrix()), quaternion);
+        }
+    }
+
+    #[test]
+    fn product_matches_matrix_composition() {
+        let a = Quaternion::from_euler(0.2, 0.7, -0.4);
+        let b = Quaternion::from_axis_angle(Vector3::new(0.3, -1.0, 0.2), 1.4);
+        assert_same_matrix(&(a * b).to_matrix(), &a.to_matrix().compose(&b.to_matrix()));
+    }
+
+    #[test]
+    fn slerp_endpoints_and_midpoint() {
+        let start = Quaternion::identity();
+        let end = Quaternion::from_axis_angle(Vector3::new(0.0, 1.0, 0.0), FRAC_PI_2);
+        assert_same_rotation(start.slerp(&end, 0.0), start);
+        assert_same_rotation(start.slerp(&end, 1.0), end);
+        assert_same_rotation(
+            start.slerp(&end, 0.5),
+            Quaternion::from_axis_angle(Vector3::new(0.0, 1.0, 0.0), FRAC_PI_4),
+        );
+    }
+
+    #[test]
+    fn slerp_takes_the_short_way() {
+        let start = Quaternion::from_axis_angle(Vector3::new(0.0, 0.0, 1.0), 0.1);
+        let end = Quaternion::from_axis_angle(Vector3::new(0.0, 0.0, 1.0), 0.5);
+        let flipped = Quaternion::new(-end.w, -end.x, -end.y, -end.z);
+        assert_same_rotation(
+            start.slerp(&flipped, 0.5),
+            Quaternion::from_axis_angle(Vector3::new(0.0, 0.0, 1.0), 0.3),
+        );
+    }
+}
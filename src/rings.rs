@@ -0,0 +1,151 @@
+// ============================================================================
+// SISTEMA DE ANILLOS
+// Geometría propia (un anillo plano en el ecuador) con su propio shader: bandas
+// radiales de densidad, divisiones vacías, translucidez, dispersión frontal
+// y sombras entre el planeta y los anillos
+// ============================================================================
+
+use crate::vector::Vector3;
+use crate::sphere::{Mesh, PLANET_RADIUS};
+use crate::shaders::{ShaderColor, ShaderUniforms, PlanetShader, NoiseSource, NoiseBasis, smoothstep, mix, mix_color};
+
+/// Divisiones de la malla del anillo: muchas radiales para resolver las bandas
+const RING_SEGMENTS: usize = 96;
+const RING_RADIAL_STEPS: usize = 48;
+
+/// Anillos planetarios centrados en el planeta, en su plano ecuatorial (XZ del
+/// eje de giro): se inclinan con el eje. Se dibujan como una malla aparte con
+/// este mismo tipo como shader
+pub struct RingShader {
+    noise: NoiseSource,
+    /// Radio interior en unidades del objeto
+    pub inner_radius: f32,
+    /// Radio exterior en unidades del objeto
+    pub outer_radius: f32,
+}
+
+impl RingShader {
+    /// `inner` y `outer` se expresan en radios del planeta (`PLANET_RADIUS`)
+    pub fn new(seed: u32, inner: f32, outer: f32) -> Self {
+        let inner_radius = inner.max(1.0) * PLANET_RADIUS;
+        RingShader {
+            noise: NoiseSource::new(seed),
+            inner_radius,
+            outer_radius: (outer * PLANET_RADIUS).max(inner_radius + 0.01),
+        }
+    }
+
+    /// Malla plana de dos caras en el plano XZ del eje de giro. Los anillos no
+    /// giran con el planeta: su aspecto solo depende del radio
+    pub fn mesh(&self) -> Mesh {
+        Mesh::annulus(self.inner_radius, self.outer_radius, RING_SEGMENTS, RING_RADIAL_STEPS)
+    }
+
+    /// Opacidad de los anillos a una distancia `radius` del centro, en [0, 1].
+    /// `footprint` es el tamaño de un píxel en las mismas unidades que `radius`
+    pub fn density(&self, radius: f32, footprint: f32) -> f32 {
+        let width = self.outer_radius - self.inner_radius;
+        let t = (radius - self.inner_radius) / width;
+        if !(0.0..=1.0).contains(&t) {
+            return 0.0;
+        }
+
+        // === REGIONES PRINCIPALES (como C, B y A de Saturno) ===
+        let faint_inner = 0.3;
+        let dense_middle = 0.95;
+        let outer_band = 0.65;
+        let region = mix(faint_inner, dense_middle, smoothstep(0.2, 0.3, t));
+        let region = mix(region, outer_band, smoothstep(0.6, 0.62, t));
+
+        // === DIVISIONES: huecos casi vacíos despejados por lunas pastoras ===
+        let cassini = smoothstep(0.6, 0.62, t) * smoothstep(0.68, 0.66, t);
+        let encke = smoothstep(0.905, 0.915, t) * smoothstep(0.925, 0.915, t);
+        let gaps = 1.0 - cassini * 0.95 - encke * 0.8;
+
+        // === BANDAS FINAS: cientos de anillos delgados ===
+        let band_footprint = footprint / width;
+        let ringlets = self.noise.fbm3d_filtered(NoiseBasis::Simplex, t * 40.0, 0.5, 0.5, 5, band_footprint * 40.0);
+        let ringlets = (0.75 + ringlets * 0.5).clamp(0.3, 1.0);
+
+        // Bordes suaves al principio y al final del sistema
+        let edges = smoothstep(0.0, 0.03, t) * smoothstep(1.0, 0.97, t);
+
+        (region * gaps * ringlets * edges).clamp(0.0, 1.0)
+    }
+
+    /// Fracción de luz que llega a `object_position` del planeta tras atravesar
+    /// los anillos en dirección a la luz (1 = sin sombra). Punto y dirección van
+    /// en espacio de objeto, donde los anillos ocupan el plano y = 0
+    pub fn shadow_on_planet(&self, object_position: Vector3, light_direction: Vector3) -> f32 {
+        if light_direction.y.abs() < 1e-4 {
+            return 1.0;
+        }
+
+        // Intersección del rayo hacia la luz con el plano de los anillos
+        let distance = -object_position.y / light_direction.y;
+        if distance <= 0.0 {
+            return 1.0;
+        }
+        let hit = object_position + light_direction * distance;
+        1.0 - self.density(hit.length(), 0.0) * 0.85
+    }
+
+    /// Fracción de luz que llega a `world_position` de los anillos sin que la
+    /// tape el planeta (1 = iluminado). El borde de la sombra es suave
+    pub fn planet_shadow(&self, world_position: Vector3, light_direction: Vector3) -> f32 {
+        // Punto del rayo hacia la luz más cercano al centro del planeta
+        let along = -world_position.dot(&light_direction);
+        if along <= 0.0 {
+            return 1.0;
+        }
+        let closest = (world_position.dot(&world_position) - along * along).max(0.0).sqrt();
+        smoothstep(PLANET_RADIUS * 0.95, PLANET_RADIUS * 1.05, closest)
+    }
+}
+
+impl PlanetShader for RingShader {
+    fn vertex_shader(&self, position: Vector3, normal: Vector3, _uv: (f32, f32), _uniforms: &ShaderUniforms) -> (Vector3, Vector3) {
+        (position, normal)
+    }
+
+    fn fragment_shader(&self, position: Vector3, normal: Vector3, _uv: (f32, f32), uniforms: &ShaderUniforms) -> ShaderColor {
+        // === PALETA DE HIELO Y POLVO ===
+        let dusty = ShaderColor::from_rgb(170, 150, 125);          // Polvo rocoso
+        let icy = ShaderColor::from_rgb(235, 228, 212);            // Hielo limpio
+        let grey = ShaderColor::from_rgb(120, 115, 110);           // Anillo interior tenue
+
+        let object_position = uniforms.object_position(position);
+        let radius = (object_position.x * object_position.x + object_position.z * object_position.z).sqrt();
+        let density = self.density(radius, uniforms.footprint(position));
+        let t = ((radius - self.inner_radius) / (self.outer_radius - self.inner_radius)).clamp(0.0, 1.0);
+
+        // Bandas densas más heladas y brillantes; el anillo interior, gris
+        let base_color = mix_color(dusty, icy, density);
+        let base_color = mix_color(grey, base_color, smoothstep(0.15, 0.3, t));
+
+        // === ILUMINACIÓN DE UNA LÁMINA DELGADA ===
+        let light_dir = uniforms.light_direction.normalize();
+        let view_dir = (uniforms.camera_position - position).normalize();
+        let light_side = normal.dot(&light_dir);
+        let view_side = normal.dot(&view_dir);
+
+        // Cara iluminada: reflexión difusa. Cara en sombra: solo la luz que atraviesa
+        // las zonas menos densas
+        let reflected = if light_side * view_side > 0.0 { light_side.abs() } else { 0.0 };
+        let transmitted = if light_side * view_side < 0.0 { light_side.abs() * (1.0 - density) } else { 0.0 };
+
+        // Dispersión frontal: el polvo fino brilla al mirar hacia la luz a través de él
+        let forward = (-view_dir.dot(&light_dir)).max(0.0).powf(8.0) * (1.0 - density) * 1.5;
+
+        let shadow = self.planet_shadow(position, light_dir);
+        let ambient = 0.05;
+        let lighting = ambient + (reflected * 0.9 + transmitted * 0.6 + forward) * shadow;
+
+        ShaderColor::new(
+            (base_color.r * lighting).clamp(0.0, 1.0),
+            (base_color.g * lighting).clamp(0.0, 1.0),
+            (base_color.b * lighting).clamp(0.0, 1.0),
+            (density * 0.9 + forward * 0.3).min(1.0),
+        )
+    }
+}
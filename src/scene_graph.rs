@@ -0,0 +1,234 @@
+// ============================================================================
+// GRAFO DE ESCENA
+// Nodos con traslación, rotación y escala relativas a su nodo padre. La matriz
+// de mundo de cada nodo es la de su padre compuesta con la suya propia, y se
+// calcula una sola vez por fotograma en `update`. Los cuerpos, sus lunas, los
+// anillos y el punto que sigue la cámara cuelgan de nodos
+// ============================================================================
+
+use crate::vector::Vector3;
+use crate::transform::{Transform4x4, build_translation, build_uniform_scale};
+use crate::quaternion::Quaternion;
+
+/// Índice de un nodo dentro del grafo
+pub type NodeId = usize;
+
+pub struct SceneNode {
+    parent: Option<NodeId>,
+    /// Posición respecto al padre
+    pub translation: Vector3,
+    /// Orientación respecto al padre
+    pub rotation: Quaternion,
+    pub scale: f32,
+}
+
+impl SceneNode {
+    /// Matriz local: primero escala, luego rotación y por último traslación
+    pub fn local_matrix(&self) -> Transform4x4 {
+        build_translation(self.translation.x, self.translation.y, self.translation.z)
+            .compose(&self.rotation.to_matrix())
+            .compose(&build_uniform_scale(self.scale))
+    }
+}
+
+#[derive(Default)]
+pub struct SceneGraph {
+    nodes: Vec<SceneNode>,
+    /// Matrices de mundo calculadas en el último `update`
+    world_matrices: Vec<Transform4x4>,
+}
+
+impl SceneGraph {
+    pub fn new() -> Self {
+        SceneGraph { nodes: Vec::new(), world_matrices: Vec::new() }
+    }
+
+    /// Añade un nodo sin transformación colgado de `parent` (o de la raíz)
+    pub fn add_node(&mut self, parent: Option<NodeId>) -> NodeId {
+        self.nodes.push(SceneNode {
+            parent: parent.filter(|&parent| parent < self.nodes.len()),
+            translation: Vector3::new(0.0, 0.0, 0.0),
+            rotation: Quaternion::identity(),
+            scale: 1.0,
+        });
+        self.world_matrices.push(Transform4x4::identity());
+        self.nodes.len() - 1
+    }
+
+    pub fn node_mut(&mut self, id: NodeId) -> &mut SceneNode {
+        &mut self.nodes[id]
+    }
+
+    /// Cambia el padre de un nodo. Falla si el padre no existe o si el nodo
+    /// acabaría colgando de sí mismo
+    pub fn set_parent(&mut self, id: NodeId, parent: Option<NodeId>) -> Result<(), String> {
+        if let Some(parent) = parent {
+            if parent >= self.nodes.len() {
+                return Err(format!("El nodo {} no existe", parent));
+            }
+            let mut ancestor = Some(parent);
+            while let Some(current) = ancestor {
+                if current == id {
+                    return Err(format!("El nodo {} no puede colgar de su descendiente {}", id, parent));
+                }
+                ancestor = self.nodes[current].parent;
+            }
+        }
+        self.nodes[id].parent = parent;
+        Ok(())
+    }
+
+    /// Recalcula y guarda la matriz de mundo de todos los nodos. Cada padre se
+    /// resuelve antes que sus hijos, sin importar el orden en que se añadieron
+    pub fn update(&mut self) {
+        let mut resolved = vec![false; self.nodes.len()];
+        let mut chain = Vec::new();
+
+        for id in 0..self.nodes.len() {
+            // Subir hasta el primer antepasado ya resuelto (o la raíz)...
+            let mut current = Some(id);
+            while let Some(node) = current {
+                if resolved[node] {
+                    break;
+                }
+                chain.push(node);
+                current = self.nodes[node].parent;
+            }
+
+            // ...y bajar componiendo las matrices locales
+            while let Some(node) = chain.pop() {
+                let local = self.nodes[node].local_matrix();
+                self.world_matrices[node] = match self.nodes[node].parent {
+                    Some(parent) => self.world_matrices[parent].compose(&local),
+                    None => local,
+                };
+                resolved[node] = true;
+            }
+        }
+    }
+
+    /// Matriz de mundo del nodo según el último `update`
+    pub fn world_matrix(&self, id: NodeId) -> &Transform4x4 {
+        &self.world_matrices[id]
+    }
+
+    /// Matriz del nodo respecto a uno de sus antepasados: compone las matrices
+    /// locales desde el nodo hasta `ancestor` (sin incluirlo)
+    pub fn matrix_relative_to(&self, id: NodeId, ancestor: NodeId) -> Transform4x4 {
+        let mut matrix = Transform4x4::identity();
+        let mut current = Some(id);
+        while let Some(node) = current {
+            if node == ancestor {
+                break;
+            }
+            matrix = self.nodes[node].local_matrix().compose(&matrix);
+            current = self.nodes[node].parent;
+        }
+        matrix
+    }
+
+    /// Posición del origen del nodo en el mundo
+    pub fn world_position(&self, id: NodeId) -> Vector3 {
+        self.world_matrices[id].apply_to_vector(&Vector3::new(0.0, 0.0, 0.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: Vector3, b: Vector3) {
+        assert!((a - b).length() < 1e-5, "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn world_position_composes_parents() {
+        let mut graph = SceneGraph::new();
+        let root = graph.add_node(None);
+        let child = graph.add_node(Some(root));
+        graph.node_mut(root).translation = Vector3::new(1.0, 0.0, 0.0);
+        graph.node_mut(root).scale = 2.0;
+        graph.node_mut(child).translation = Vector3::new(0.0, 0.0, 3.0);
+        graph.update();
+
+        // La traslación del hijo se escala con el padre
+        assert_close(graph.world_position(child), Vector3::new(1.0, 0.0, 6.0));
+    }
+
+    #[test]
+    fn rotation_of_the_parent_carries_the_child() {
+        let mut graph = SceneGraph::new();
+        let root = graph.add_node(None);
+        let child = graph.add_node(Some(root));
+        graph.node_mut(root).rotation = Quaternion::from_axis_angle(Vector3::new(0.0, 1.0, 0.0), std::f32::consts::FRAC_PI_2);
+        graph.node_mut(child).translation = Vector3::new(1.0, 0.0, 0.0);
+        graph.update();
+
+        assert_close(graph.world_position(child), Vector3::new(0.0, 0.0, -1.0));
+    }
+
+    #[test]
+    fn reparenting_updates_cached_world_matrices() {
+        let mut graph = SceneGraph::new();
+        let first = graph.add_node(None);
+        let second = graph.add_node(None);
+        let child = graph.add_node(Some(first));
+        graph.node_mut(first).translation = Vector3::new(5.0, 0.0, 0.0);
+        graph.node_mut(second).translation = Vector3::new(0.0, -2.0, 0.0);
+        graph.node_mut(child).translation = Vector3::new(0.0, 1.0, 0.0);
+        graph.update();
+        assert_close(graph.world_position(child), Vector3::new(5.0, 1.0, 0.0));
+
+        graph.set_parent(child, Some(second)).unwrap();
+        graph.update();
+        assert_close(graph.world_position(child), Vector3::new(0.0, -1.0, 0.0));
+        // La matriz cacheada no cambia hasta el siguiente `update`
+        graph.set_parent(child, None).unwrap();
+        assert_close(graph.world_position(child), Vector3::new(0.0, -1.0, 0.0));
+        graph.update();
+        assert_close(graph.world_position(child), Vector3::new(0.0, 1.0, 0.0));
+    }
+
+    #[test]
+    fn parents_added_after_their_children_resolve_first() {
+        let mut graph = SceneGraph::new();
+        let child = graph.add_node(None);
+        let parent = graph.add_node(None);
+        graph.set_parent(child, Some(parent)).unwrap();
+        graph.node_mut(parent).translation = Vector3::new(0.0, 0.0, 4.0);
+        graph.node_mut(child).translation = Vector3::new(1.0, 0.0, 0.0);
+        graph.update();
+
+        assert_close(graph.world_position(child), Vector3::new(1.0, 0.0, 4.0));
+    }
+
+    #[test]
+    fn set_parent_rejects_cycles_and_missing_nodes() {
+        let mut graph = SceneGraph::new();
+        let root = graph.add_node(None);
+        let middle = graph.add_node(Some(root));
+        let leaf = graph.add_node(Some(middle));
+
+        assert!(graph.set_parent(root, Some(leaf)).is_err());
+        assert!(graph.set_parent(middle, Some(middle)).is_err());
+        assert!(graph.set_parent(leaf, Some(42)).is_err());
+        // Un intento fallido no cambia la jerarquía
+        graph.node_mut(root).translation = Vector3::new(2.0, 0.0, 0.0);
+        graph.update();
+        assert_close(graph.world_position(leaf), Vector3::new(2.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn matrix_relative_to_stops_at_the_ancestor() {
+        let mut graph = SceneGraph::new();
+        let root = graph.add_node(None);
+        let middle = graph.add_node(Some(root));
+        let leaf = graph.add_node(Some(middle));
+        graph.node_mut(root).translation = Vector3::new(10.0, 0.0, 0.0);
+        graph.node_mut(middle).translation = Vector3::new(0.0, 1.0, 0.0);
+        graph.node_mut(leaf).translation = Vector3::new(0.0, 0.0, 1.0);
+
+        let relative = graph.matrix_relative_to(leaf, root);
+        assert_close(relative.apply_to_vector(&Vector3::new(0.0, 0.0, 0.0)), Vector3::new(0.0, 1.0, 1.0));
+    }
+}
@@ -1,4 +1,7 @@
 use crate::vector::Vector3;
+use crate::transform::Transform4x4;
+use crate::rings::RingShader;
+use crate::sphere::Mesh;
 
 #[derive(Debug, Clone, Copy)]
 pub struct ShaderColor {
@@ -36,130 +39,677 @@ impl ShaderColor {
     pub const YELLOW: ShaderColor = ShaderColor { r: 1.0, g: 1.0, b: 0.0, a: 1.0 };
 }
 
+#[derive(Debug, Clone, Copy)]
 pub struct ShaderUniforms {
     pub time: f32,
     pub light_direction: Vector3,
     pub camera_position: Vector3,
+    /// Inversa de la matriz de modelo del planeta (rotación incluida)
+    pub world_to_object: Transform4x4,
+    /// Ángulo (radianes) que abarca un píxel de pantalla; 0 desactiva el filtrado
+    pub pixel_angle: f32,
+}
+
+impl ShaderUniforms {
+    /// Lleva un punto del mundo al espacio de objeto del planeta
+    pub fn object_position(&self, world_position: Vector3) -> Vector3 {
+        self.world_to_object.apply_to_vector(&world_position)
+    }
+
+    /// Tamaño en unidades del mundo que cubre un píxel en ese punto. Multiplicado
+    /// por la escala con la que se muestrea un ruido da el `footprint` que
+    /// esperan las variantes `_filtered` y `NoiseNode::eval_filtered`
+    pub fn footprint(&self, world_position: Vector3) -> f32 {
+        (world_position - self.camera_position).length() * self.pixel_angle
+    }
+}
+
+// Parámetros con nombre que recibe la fábrica de cada shader (semilla, escalas, etc.)
+#[derive(Debug, Clone, Default)]
+pub struct ShaderParams {
+    values: Vec<(String, f32)>,
+}
+
+impl ShaderParams {
+    pub fn new() -> Self {
+        ShaderParams { values: Vec::new() }
+    }
+
+    pub fn with(mut self, name: &str, value: f32) -> Self {
+        self.set(name, value);
+        self
+    }
+
+    pub fn set(&mut self, name: &str, value: f32) {
+        match self.values.iter_mut().find(|(key, _)| key == name) {
+            Some(entry) => entry.1 = value,
+            None => self.values.push((name.to_string(), value)),
+        }
+    }
+
+    pub fn get(&self, name: &str, default: f32) -> f32 {
+        self.values
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| *value)
+            .unwrap_or(default)
+    }
+
+    /// Semilla de ruido del planeta (parámetro "seed")
+    pub fn seed(&self) -> u32 {
+        self.get("seed", 0.0).max(0.0) as u32
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, f32)> {
+        self.values.iter().map(|(key, value)| (key.as_str(), *value))
+    }
 }
 
 pub trait PlanetShader {
     fn vertex_shader(&self, position: Vector3, normal: Vector3, uv: (f32, f32), uniforms: &ShaderUniforms) -> (Vector3, Vector3);
     fn fragment_shader(&self, position: Vector3, normal: Vector3, uv: (f32, f32), uniforms: &ShaderUniforms) -> ShaderColor;
+
+    /// Halo que el renderizador dibuja detrás del disco (corona, resplandor).
+    /// Por defecto el planeta no tiene halo
+    fn halo(&self, _uniforms: &ShaderUniforms) -> Option<HaloStyle> {
+        None
+    }
+
+    /// Luz que emite la superficie por sí misma (ya sumada en `fragment_shader`).
+    /// El renderizador la usa para el bloom y para no oscurecerla con las sombras
+    /// de las capas; por defecto el planeta no emite
+    fn emission(&self, _position: Vector3, _normal: Vector3, _uniforms: &ShaderUniforms) -> ShaderColor {
+        ShaderColor::BLACK
+    }
+
+    /// Color y emisión de un mismo punto. Por defecto llama a `fragment_shader`
+    /// y a `emission` por separado; los shaders cuya emisión sale de los mismos
+    /// cálculos que el color lo sobrescriben para hacerlos una sola vez
+    fn shade(&self, position: Vector3, normal: Vector3, uv: (f32, f32), uniforms: &ShaderUniforms) -> (ShaderColor, ShaderColor) {
+        (self.fragment_shader(position, normal, uv, uniforms), self.emission(position, normal, uniforms))
+    }
+
+    /// Anillos que el renderizador dibuja como malla aparte alrededor del planeta.
+    /// Por defecto el planeta no tiene anillos
+    fn rings(&self) -> Option<&RingShader> {
+        None
+    }
+
+    /// Malla propia del cuerpo (asteroides, lunas irregulares...). Por defecto
+    /// se usa la esfera de `sphere.obj`
+    fn mesh(&self) -> Option<Mesh> {
+        None
+    }
+
+    /// Capas que el renderizador dibuja sobre la superficie, en orden
+    /// (nubes, atmósfera...). Por defecto el planeta solo tiene superficie
+    fn layers(&self) -> &[RenderLayer] {
+        &[]
+    }
+
+    /// Fracción de la luz que bloquea una capa en `object_position` (espacio de
+    /// la capa). El renderizador la usa para proyectar sombras sobre la superficie
+    fn shadow_opacity(&self, _object_position: Vector3, _uniforms: &ShaderUniforms) -> f32 {
+        0.0
+    }
+
+    /// Rampas de color del shader, en un orden estable, para exportarlas o
+    /// sustituirlas por paletas externas. Por defecto no tiene ninguna
+    fn color_ramps_mut(&mut self) -> Vec<&mut ColorRamp> {
+        Vec::new()
+    }
+}
+
+/// Capa esférica de un planeta con su propio shader (nubes, atmósfera...)
+pub struct RenderLayer {
+    pub shader: Box<dyn PlanetShader>,
+    /// Radio de la capa relativo a la superficie (1.0 = misma esfera)
+    pub radius_scale: f32,
+    /// Velocidad de giro propia, independiente de la de la superficie
+    pub rotation_speed: f32,
 }
 
+/// Resplandor alrededor del disco, dibujado como billboard orientado a la cámara
+#[derive(Debug, Clone, Copy)]
+pub struct HaloStyle {
+    /// Color junto al borde del disco; el alfa es la opacidad inicial
+    pub color: ShaderColor,
+    /// Radio exterior relativo al radio del planeta (1.0 = sin halo)
+    pub extent: f32,
+    /// Amplitud de las serpentinas animadas (0 = halo circular)
+    pub streamers: f32,
+}
+
+
+// ============================================================================
+// RUIDO CON SEMILLA
+// Todas las funciones de ruido pertenecen a una fuente con semilla, así cada
+// planeta puede generar variantes distintas pero reproducibles
+// ============================================================================
 
-// Función de ruido Perlin
-pub fn perlin_noise(x: f32, y: f32, z: f32) -> f32 {
-    let xi = x.floor() as i32;
-    let yi = y.floor() as i32;
-    let zi = z.floor() as i32;
+/// Base de ruido usada por las funciones fractales (`fbm3d_basis`, etc.)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NoiseBasis {
+    /// Ruido de valor trilineal; rango [-1, 1]
+    Value,
+    /// Ruido de gradiente de Perlin mejorado; rango aprox. [-1, 1]
+    Perlin,
+    /// Ruido simplex; rango aprox. [-1, 1]
+    Simplex,
+}
+
+/// Fuente de ruido determinista: la misma semilla produce siempre el mismo campo
+#[derive(Debug, Clone)]
+pub struct NoiseSource {
+    seed: u32,
+}
+
+impl NoiseSource {
+    pub fn new(seed: u32) -> Self {
+        NoiseSource { seed }
+    }
     
-    let xf = x - x.floor();
-    let yf = y - y.floor();
-    let zf = z - z.floor();
+    // Hash de un punto de la grilla entera, mezclado con la semilla
+    fn lattice_hash(&self, x: i32, y: i32, z: i32, w: i32) -> u32 {
+        let mut h = hash_u32(self.seed ^ 0x9e3779b9);
+        h = hash_u32(h ^ x as u32);
+        h = hash_u32(h ^ y as u32);
+        h = hash_u32(h ^ z as u32);
+        hash_u32(h ^ w as u32)
+    }
     
-    let u = xf * xf * (3.0 - 2.0 * xf);
-    let v = yf * yf * (3.0 - 2.0 * yf);
-    let w = zf * zf * (3.0 - 2.0 * zf);
+    /// Ruido de valor 3D (interpolación trilineal con suavizado cúbico).
+    /// Rango: [-1, 1]
+    pub fn value_noise(&self, x: f32, y: f32, z: f32) -> f32 {
+        let xi = x.floor() as i32;
+        let yi = y.floor() as i32;
+        let zi = z.floor() as i32;
+        
+        let xf = x - x.floor();
+        let yf = y - y.floor();
+        let zf = z - z.floor();
+        
+        let u = xf * xf * (3.0 - 2.0 * xf);
+        let v = yf * yf * (3.0 - 2.0 * yf);
+        let w = zf * zf * (3.0 - 2.0 * zf);
+        
+        // Valor en [-1, 1] para cada esquina de la celda
+        let hash = |x: i32, y: i32, z: i32| -> f32 {
+            hash_to_unit(self.lattice_hash(x, y, z, 0)) * 2.0 - 1.0
+        };
+        
+        let aaa = hash(xi, yi, zi);
+        let aba = hash(xi, yi + 1, zi);
+        let aab = hash(xi, yi, zi + 1);
+        let abb = hash(xi, yi + 1, zi + 1);
+        let baa = hash(xi + 1, yi, zi);
+        let bba = hash(xi + 1, yi + 1, zi);
+        let bab = hash(xi + 1, yi, zi + 1);
+        let bbb = hash(xi + 1, yi + 1, zi + 1);
+        
+        let x1 = mix(aaa, baa, u);
+        let x2 = mix(aba, bba, u);
+        let x3 = mix(aab, bab, u);
+        let x4 = mix(abb, bbb, u);
+        
+        let y1 = mix(x1, x2, v);
+        let y2 = mix(x3, x4, v);
+        
+        mix(y1, y2, w)
+    }
     
-    let hash = |x: i32, y: i32, z: i32| -> f32 {
-        let n = x.wrapping_add(y.wrapping_mul(57)).wrapping_add(z.wrapping_mul(113));
-        let n = (n << 13) ^ n;
-        let nn = n.wrapping_mul(n.wrapping_mul(n.wrapping_mul(15731).wrapping_add(789221)).wrapping_add(1376312589));
-        1.0 - ((nn & 0x7fffffff) as f32 / 1073741824.0)
-    };
+    /// Ruido de gradiente de Perlin 3D con la curva quíntica mejorada (6t⁵ - 15t⁴ + 10t³).
+    /// Vale 0 en los puntos enteros. Rango: aprox. [-1, 1]
+    pub fn perlin_noise(&self, x: f32, y: f32, z: f32) -> f32 {
+        let xi = x.floor() as i32;
+        let yi = y.floor() as i32;
+        let zi = z.floor() as i32;
+        
+        let xf = x - x.floor();
+        let yf = y - y.floor();
+        let zf = z - z.floor();
+        
+        let u = quintic_fade(xf);
+        let v = quintic_fade(yf);
+        let w = quintic_fade(zf);
+        
+        let corner = |dx: i32, dy: i32, dz: i32| -> f32 {
+            let hash = self.lattice_hash(xi + dx, yi + dy, zi + dz, 0);
+            gradient3(hash, xf - dx as f32, yf - dy as f32, zf - dz as f32)
+        };
+        
+        let x1 = mix(corner(0, 0, 0), corner(1, 0, 0), u);
+        let x2 = mix(corner(0, 1, 0), corner(1, 1, 0), u);
+        let x3 = mix(corner(0, 0, 1), corner(1, 0, 1), u);
+        let x4 = mix(corner(0, 1, 1), corner(1, 1, 1), u);
+        
+        let y1 = mix(x1, x2, v);
+        let y2 = mix(x3, x4, v);
+        
+        mix(y1, y2, w)
+    }
     
-    let aaa = hash(xi, yi, zi);
-    let aba = hash(xi, yi + 1, zi);
-    let aab = hash(xi, yi, zi + 1);
-    let abb = hash(xi, yi + 1, zi + 1);
-    let baa = hash(xi + 1, yi, zi);
-    let bba = hash(xi + 1, yi + 1, zi);
-    let bab = hash(xi + 1, yi, zi + 1);
-    let bbb = hash(xi + 1, yi + 1, zi + 1);
+    /// Ruido simplex 3D. Rango: aprox. [-1, 1]
+    pub fn simplex3(&self, x: f32, y: f32, z: f32) -> f32 {
+        const F3: f32 = 1.0 / 3.0;
+        const G3: f32 = 1.0 / 6.0;
+        
+        let skew = (x + y + z) * F3;
+        let i = (x + skew).floor() as i32;
+        let j = (y + skew).floor() as i32;
+        let k = (z + skew).floor() as i32;
+        let unskew = (i + j + k) as f32 * G3;
+        let x0 = x - (i as f32 - unskew);
+        let y0 = y - (j as f32 - unskew);
+        let z0 = z - (k as f32 - unskew);
+        
+        // Ordenar las coordenadas para encontrar el tetraedro que contiene el punto
+        let ((i1, j1, k1), (i2, j2, k2)) = if x0 >= y0 {
+            if y0 >= z0 {
+                ((1, 0, 0), (1, 1, 0))
+            } else if x0 >= z0 {
+                ((1, 0, 0), (1, 0, 1))
+            } else {
+                ((0, 0, 1), (1, 0, 1))
+            }
+        } else if y0 < z0 {
+            ((0, 0, 1), (0, 1, 1))
+        } else if x0 < z0 {
+            ((0, 1, 0), (0, 1, 1))
+        } else {
+            ((0, 1, 0), (1, 1, 0))
+        };
+        
+        let corners = [
+            (0, 0, 0, 0.0),
+            (i1, j1, k1, G3),
+            (i2, j2, k2, 2.0 * G3),
+            (1, 1, 1, 3.0 * G3),
+        ];
+        
+        let mut total = 0.0;
+        for (di, dj, dk, offset) in corners {
+            let dx = x0 - di as f32 + offset;
+            let dy = y0 - dj as f32 + offset;
+            let dz = z0 - dk as f32 + offset;
+            let t = 0.6 - dx * dx - dy * dy - dz * dz;
+            if t > 0.0 {
+                let hash = self.lattice_hash(i + di, j + dj, k + dk, 0);
+                total += t.powi(4) * gradient3(hash, dx, dy, dz);
+            }
+        }
+        
+        32.0 * total
+    }
     
-    let x1 = mix(aaa, baa, u);
-    let x2 = mix(aba, bba, u);
-    let x3 = mix(aab, bab, u);
-    let x4 = mix(abb, bbb, u);
+    /// Ruido simplex 4D. Usando el tiempo como cuarta coordenada se obtiene
+    /// ruido 3D animado sin saltos ni deriva. Rango: aprox. [-1, 1]
+    pub fn simplex4(&self, x: f32, y: f32, z: f32, w: f32) -> f32 {
+        const F4: f32 = 0.309017;  // (√5 - 1) / 4
+        const G4: f32 = 0.1381966; // (5 - √5) / 20
+        
+        let skew = (x + y + z + w) * F4;
+        let i = (x + skew).floor() as i32;
+        let j = (y + skew).floor() as i32;
+        let k = (z + skew).floor() as i32;
+        let l = (w + skew).floor() as i32;
+        let unskew = (i + j + k + l) as f32 * G4;
+        let p0 = [
+            x - (i as f32 - unskew),
+            y - (j as f32 - unskew),
+            z - (k as f32 - unskew),
+            w - (l as f32 - unskew),
+        ];
+        
+        // El rango de cada coordenada decide el orden de recorrido del simplex
+        let mut rank = [0; 4];
+        for a in 0..4 {
+            for b in (a + 1)..4 {
+                if p0[a] > p0[b] {
+                    rank[a] += 1;
+                } else {
+                    rank[b] += 1;
+                }
+            }
+        }
+        
+        let mut total = 0.0;
+        for corner in 0..5 {
+            // corner 0 = origen, corner 4 = (1,1,1,1)
+            let step = |axis: usize| -> i32 { (rank[axis] >= 4 - corner) as i32 };
+            let offset = [step(0), step(1), step(2), step(3)];
+            let d = [
+                p0[0] - offset[0] as f32 + corner as f32 * G4,
+                p0[1] - offset[1] as f32 + corner as f32 * G4,
+                p0[2] - offset[2] as f32 + corner as f32 * G4,
+                p0[3] - offset[3] as f32 + corner as f32 * G4,
+            ];
+            let t = 0.6 - d[0] * d[0] - d[1] * d[1] - d[2] * d[2] - d[3] * d[3];
+            if t > 0.0 {
+                let hash = self.lattice_hash(i + offset[0], j + offset[1], k + offset[2], l + offset[3]);
+                total += t.powi(4) * gradient4(hash, d[0], d[1], d[2], d[3]);
+            }
+        }
+        
+        27.0 * total
+    }
     
-    let y1 = mix(x1, x2, v);
-    let y2 = mix(x3, x4, v);
+    /// Evalúa la base indicada en un punto 3D
+    pub fn sample3(&self, basis: NoiseBasis, x: f32, y: f32, z: f32) -> f32 {
+        match basis {
+            NoiseBasis::Value => self.value_noise(x, y, z),
+            NoiseBasis::Perlin => self.perlin_noise(x, y, z),
+            NoiseBasis::Simplex => self.simplex3(x, y, z),
+        }
+    }
     
-    mix(y1, y2, w)
-}
-
-// Funciones de ruido mejoradas para efectos procedurales
-pub fn simple_noise(x: f32, y: f32) -> f32 {
-    let seed = ((x * 12.9898 + y * 78.233) * 43758.5453).sin().abs();
-    (seed * 1000.0).fract()
-}
-
-pub fn fbm(mut x: f32, mut y: f32, octaves: i32) -> f32 {
-    let mut value = 0.0;
-    let mut amplitude = 0.5;
+    /// Ruido blanco: un valor independiente por cada punto de entrada.
+    /// Usa los bits exactos de las coordenadas, así no pierde precisión
+    /// con coordenadas grandes. Rango: [0, 1)
+    pub fn simple_noise(&self, x: f32, y: f32) -> f32 {
+        let h = hash_u32(self.seed ^ hash_u32(x.to_bits() ^ hash_u32(y.to_bits())));
+        hash_to_unit(h)
+    }
+    
+    /// Suma fractal de ruido blanco 2D, filtrada según la huella del píxel
+    /// (`footprint`, en las mismas unidades que x e y). Las octavas que no se
+    /// pueden representar se sustituyen por su valor medio. Rango: [0, 1)
+    pub fn fbm_filtered(&self, mut x: f32, mut y: f32, octaves: i32, footprint: f32) -> f32 {
+        let mut value = 0.0;
+        let mut amplitude = 0.5;
+        let mut frequency = 1.0;
+        
+        for _ in 0..octaves {
+            // Las octavas irrepresentables aportan su media sin evaluarse
+            let weight = octave_fade(frequency, footprint);
+            let noise = if weight > 0.0 { self.simple_noise(x, y) } else { 0.5 };
+            value += amplitude * mix(0.5, noise, weight);
+            x *= 2.0;
+            y *= 2.0;
+            frequency *= 2.0;
+            amplitude *= 0.5;
+        }
+        
+        value
+    }
     
-    for _ in 0..octaves {
-        value += amplitude * simple_noise(x, y);
-        x *= 2.0;
-        y *= 2.0;
-        amplitude *= 0.5;
+    /// Ruido fractal 3D sobre ruido de valor. Rango: (-1, 1)
+    pub fn fbm3d(&self, x: f32, y: f32, z: f32, octaves: i32) -> f32 {
+        self.fbm3d_basis(NoiseBasis::Value, x, y, z, octaves)
     }
     
-    value
-}
-
-// Función de ruido 3D
-pub fn fbm3d(x: f32, y: f32, z: f32, octaves: i32) -> f32 {
-    let mut value = 0.0;
-    let mut amplitude = 0.5;
-    let mut frequency = 1.0;
+    /// Ruido fractal 3D sobre la base indicada. Rango: aprox. (-1, 1)
+    pub fn fbm3d_basis(&self, basis: NoiseBasis, x: f32, y: f32, z: f32, octaves: i32) -> f32 {
+        self.fbm3d_filtered(basis, x, y, z, octaves, 0.0)
+    }
     
-    for _ in 0..octaves {
-        value += amplitude * perlin_noise(x * frequency, y * frequency, z * frequency);
-        frequency *= 2.0;
-        amplitude *= 0.5;
+    /// `fbm3d_basis` filtrado según la huella del píxel: las octavas por encima
+    /// del límite de Nyquist se desvanecen (su media es 0) y dejan de evaluarse
+    pub fn fbm3d_filtered(&self, basis: NoiseBasis, x: f32, y: f32, z: f32, octaves: i32, footprint: f32) -> f32 {
+        let mut value = 0.0;
+        let mut amplitude = 0.5;
+        let mut frequency = 1.0;
+        
+        for _ in 0..octaves {
+            let weight = octave_fade(frequency, footprint);
+            if weight <= 0.0 {
+                break;
+            }
+            value += amplitude * weight * self.sample3(basis, x * frequency, y * frequency, z * frequency);
+            frequency *= 2.0;
+            amplitude *= 0.5;
+        }
+        
+        value
     }
     
-    value
-}
+    /// Ruido fractal 4D sobre simplex, para animaciones continuas en el tiempo,
+    /// filtrado según la huella del píxel (medida en x, y, z). Rango: aprox. (-1, 1)
+    pub fn fbm4d_filtered(&self, x: f32, y: f32, z: f32, w: f32, octaves: i32, footprint: f32) -> f32 {
+        let mut value = 0.0;
+        let mut amplitude = 0.5;
+        let mut frequency = 1.0;
+        
+        for _ in 0..octaves {
+            let weight = octave_fade(frequency, footprint);
+            if weight <= 0.0 {
+                break;
+            }
+            value += amplitude * weight * self.simplex4(x * frequency, y * frequency, z * frequency, w * frequency);
+            frequency *= 2.0;
+            amplitude *= 0.5;
+        }
+        
+        value
+    }
 
-// Función de ruido Voronoi para efectos de celdas
-pub fn voronoi_noise(x: f32, y: f32) -> f32 {
-    let cell_x = x.floor();
-    let cell_y = y.floor();
-    let mut min_dist = f32::INFINITY;
+    /// Ruido celular (Worley) 3D con la métrica indicada. Devuelve F1, F2,
+    /// el punto característico más cercano y un id estable de su celda.
+    pub fn cellular3(&self, x: f32, y: f32, z: f32, metric: DistanceMetric) -> CellularSample {
+        let cell_x = x.floor() as i32;
+        let cell_y = y.floor() as i32;
+        let cell_z = z.floor() as i32;
+        let mut sample = CellularSample::empty();
+        
+        for i in -1..=1 {
+            for j in -1..=1 {
+                for k in -1..=1 {
+                    let (nx, ny, nz) = (cell_x + i, cell_y + j, cell_z + k);
+                    let cell_id = self.lattice_hash(nx, ny, nz, 0);
+                    let jitter_y = hash_u32(cell_id);
+                    let jitter_z = hash_u32(jitter_y);
+                    let feature = Vector3::new(
+                        nx as f32 + hash_to_unit(cell_id),
+                        ny as f32 + hash_to_unit(jitter_y),
+                        nz as f32 + hash_to_unit(jitter_z),
+                    );
+                    
+                    let dist = metric.distance(&[x - feature.x, y - feature.y, z - feature.z]);
+                    sample.insert(dist, feature, 0.0, cell_id);
+                }
+            }
+        }
+        
+        sample
+    }
     
-    for i in -1..=1 {
-        for j in -1..=1 {
-            let neighbor_x = cell_x + i as f32;
-            let neighbor_y = cell_y + j as f32;
-            let point_x = neighbor_x + simple_noise(neighbor_x, neighbor_y);
-            let point_y = neighbor_y + simple_noise(neighbor_y, neighbor_x);
-            
-            let dist = ((x - point_x).powi(2) + (y - point_y).powi(2)).sqrt();
-            min_dist = min_dist.min(dist);
+    /// Ruido celular 4D; la cuarta coordenada (por ejemplo el tiempo) hace
+    /// que las celdas evolucionen de forma continua. `feature_w` guarda la
+    /// cuarta coordenada del punto más cercano.
+    pub fn cellular4(&self, x: f32, y: f32, z: f32, w: f32, metric: DistanceMetric) -> CellularSample {
+        let cell = [x.floor() as i32, y.floor() as i32, z.floor() as i32, w.floor() as i32];
+        let mut sample = CellularSample::empty();
+        
+        for i in -1..=1 {
+            for j in -1..=1 {
+                for k in -1..=1 {
+                    for l in -1..=1 {
+                        let (nx, ny, nz, nw) = (cell[0] + i, cell[1] + j, cell[2] + k, cell[3] + l);
+                        let cell_id = self.lattice_hash(nx, ny, nz, nw);
+                        let jitter_y = hash_u32(cell_id);
+                        let jitter_z = hash_u32(jitter_y);
+                        let jitter_w = hash_u32(jitter_z);
+                        let feature = Vector3::new(
+                            nx as f32 + hash_to_unit(cell_id),
+                            ny as f32 + hash_to_unit(jitter_y),
+                            nz as f32 + hash_to_unit(jitter_z),
+                        );
+                        let feature_w = nw as f32 + hash_to_unit(jitter_w);
+                        
+                        let dist = metric.distance(&[x - feature.x, y - feature.y, z - feature.z, w - feature_w]);
+                        sample.insert(dist, feature, feature_w, cell_id);
+                    }
+                }
+            }
         }
+        
+        sample
     }
     
-    min_dist
+    /// Ruido ridge para efectos de montañas, filtrado según la huella del
+    /// píxel; las crestas que no se pueden representar se sustituyen por su
+    /// media (0.5). Rango: [0, 1)
+    pub fn ridge_noise_filtered(&self, x: f32, y: f32, octaves: i32, footprint: f32) -> f32 {
+        let mut value = 0.0;
+        let mut amplitude = 0.5;
+        let mut frequency = 1.0;
+        
+        for _ in 0..octaves {
+            let weight = octave_fade(frequency, footprint);
+            let ridge = if weight > 0.0 {
+                let n = self.simple_noise(x * frequency, y * frequency);
+                1.0 - (2.0 * n - 1.0).abs()
+            } else {
+                0.5
+            };
+            value += mix(0.5, ridge, weight) * amplitude;
+            frequency *= 2.0;
+            amplitude *= 0.5;
+        }
+        
+        value
+    }
+}
+
+/// Métrica de distancia para el ruido celular
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DistanceMetric {
+    /// Celdas redondeadas
+    Euclidean,
+    /// Celdas con forma de diamante
+    Manhattan,
+    /// Celdas cuadradas
+    Chebyshev,
+}
+
+impl DistanceMetric {
+    fn distance(&self, delta: &[f32]) -> f32 {
+        match self {
+            DistanceMetric::Euclidean => delta.iter().map(|d| d * d).sum::<f32>().sqrt(),
+            DistanceMetric::Manhattan => delta.iter().map(|d| d.abs()).sum(),
+            DistanceMetric::Chebyshev => delta.iter().fold(0.0, |max, d| max.max(d.abs())),
+        }
+    }
 }
 
-// Función de ruido ridge para efectos de montañas
-pub fn ridge_noise(x: f32, y: f32, octaves: i32) -> f32 {
-    let mut value = 0.0;
-    let mut amplitude = 0.5;
-    let mut frequency = 1.0;
+/// Resultado del ruido celular: distancias a los dos puntos más cercanos,
+/// el punto más cercano y el id de su celda (igual para toda la celda)
+#[derive(Debug, Clone, Copy)]
+pub struct CellularSample {
+    pub f1: f32,
+    pub f2: f32,
+    pub feature: Vector3,
+    pub feature_w: f32,
+    pub cell_id: u32,
+}
+
+impl CellularSample {
+    fn empty() -> Self {
+        CellularSample {
+            f1: f32::INFINITY,
+            f2: f32::INFINITY,
+            feature: Vector3::new(0.0, 0.0, 0.0),
+            feature_w: 0.0,
+            cell_id: 0,
+        }
+    }
+    
+    fn insert(&mut self, dist: f32, feature: Vector3, feature_w: f32, cell_id: u32) {
+        if dist < self.f1 {
+            self.f2 = self.f1;
+            self.f1 = dist;
+            self.feature = feature;
+            self.feature_w = feature_w;
+            self.cell_id = cell_id;
+        } else if dist < self.f2 {
+            self.f2 = dist;
+        }
+    }
     
-    for _ in 0..octaves {
-        let n = simple_noise(x * frequency, y * frequency);
-        let ridge = 1.0 - (2.0 * n - 1.0).abs();
-        value += ridge * amplitude;
-        frequency *= 2.0;
-        amplitude *= 0.5;
+    /// F2 - F1: cero sobre los bordes entre celdas
+    pub fn edge_distance(&self) -> f32 {
+        self.f2 - self.f1
     }
     
-    value
+    /// Valor aleatorio estable en [0, 1) para toda la celda. Se mezcla con una
+    /// sal propia: `cell_id` sin más es la x del punto característico
+    pub fn cell_value(&self) -> f32 {
+        hash_to_unit(hash_u32(self.cell_id ^ CELL_VALUE_SALT))
+    }
+}
+
+/// Sal del valor por celda, independiente del desplazamiento del punto
+const CELL_VALUE_SALT: u32 = 0x5bd1e995;
+
+/// Hash entero de alta calidad (lowbias32 de Chris Wellons): cada bit de
+/// entrada afecta a todos los bits de salida
+pub fn hash_u32(mut x: u32) -> u32 {
+    x ^= x >> 16;
+    x = x.wrapping_mul(0x7feb352d);
+    x ^= x >> 15;
+    x = x.wrapping_mul(0x846ca68b);
+    x ^= x >> 16;
+    x
+}
+
+/// Convierte un hash en un flotante uniforme en [0, 1) usando los 24 bits altos
+pub fn hash_to_unit(hash: u32) -> f32 {
+    (hash >> 8) as f32 / 16_777_216.0
+}
+
+/// Número aleatorio en [0, 1) en la posición `index` de la secuencia de `seed`
+pub fn seeded_random(seed: u32, index: u32) -> f32 {
+    hash_to_unit(hash_u32(seed.wrapping_mul(0x9e37_79b9) ^ hash_u32(index)))
+}
+
+/// Dirección uniforme sobre la esfera unitaria; consume las posiciones
+/// `index` e `index + 1` de la secuencia
+pub fn seeded_direction(seed: u32, index: u32) -> Vector3 {
+    let z = seeded_random(seed, index) * 2.0 - 1.0;
+    let angle = seeded_random(seed, index + 1) * std::f32::consts::TAU;
+    let ring = (1.0 - z * z).max(0.0).sqrt();
+    Vector3::new(ring * angle.cos(), ring * angle.sin(), z)
+}
+
+// Curva de suavizado quíntica de Perlin: derivadas primera y segunda nulas en 0 y 1
+fn quintic_fade(t: f32) -> f32 {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+// 12 gradientes hacia las aristas del cubo (16 entradas, como el Perlin mejorado)
+fn gradient3(hash: u32, x: f32, y: f32, z: f32) -> f32 {
+    let h = hash & 15;
+    let u = if h < 8 { x } else { y };
+    let v = if h < 4 {
+        y
+    } else if h == 12 || h == 14 {
+        x
+    } else {
+        z
+    };
+    (if h & 1 == 0 { u } else { -u }) + (if h & 2 == 0 { v } else { -v })
+}
+
+// 32 gradientes hacia las aristas del hipercubo (una coordenada en cero)
+fn gradient4(hash: u32, x: f32, y: f32, z: f32, w: f32) -> f32 {
+    let h = hash & 31;
+    let (a, b, c) = match h >> 3 {
+        0 => (y, z, w),
+        1 => (x, z, w),
+        2 => (x, y, w),
+        _ => (x, y, z),
+    };
+    (if h & 4 == 0 { a } else { -a }) + (if h & 2 == 0 { b } else { -b }) + (if h & 1 == 0 { c } else { -c })
+}
+
+/// Genera la siguiente semilla de la secuencia de forma determinista (splitmix32)
+pub fn next_seed(seed: u32) -> u32 {
+    let mut z = seed.wrapping_add(0x9e3779b9);
+    z = (z ^ (z >> 16)).wrapping_mul(0x85ebca6b);
+    z = (z ^ (z >> 13)).wrapping_mul(0xc2b2ae35);
+    z ^= z >> 16;
+    // Mantener la semilla representable exactamente como parámetro f32
+    z % 1_000_000
 }
 
 pub fn smoothstep(edge0: f32, edge1: f32, x: f32) -> f32 {
@@ -167,6 +717,13 @@ pub fn smoothstep(edge0: f32, edge1: f32, x: f32) -> f32 {
     t * t * (3.0 - 2.0 * t)
 }
 
+/// Peso de una octava de ruido según la huella del píxel: 1 mientras su
+/// frecuencia está holgadamente bajo el límite de Nyquist (0.5 ciclos por
+/// píxel) y 0 cuando ya no puede representarse. `footprint` = 0 no filtra
+pub fn octave_fade(frequency: f32, footprint: f32) -> f32 {
+    1.0 - smoothstep(0.25, 0.5, frequency * footprint)
+}
+
 pub fn mix(a: f32, b: f32, t: f32) -> f32 {
     a * (1.0 - t) + b * t
 }
@@ -180,3 +737,1112 @@ pub fn mix_color(a: ShaderColor, b: ShaderColor, t: f32) -> ShaderColor {
     )
 }
 
+// ============================================================================
+// MUESTREO ESFÉRICO
+// Las UV de la malla no sirven para texturizar la esfera (costura donde u pasa
+// de 1 a 0 y polos pellizcados); estas funciones parten de la dirección 3D
+// de la superficie en espacio de objeto (`ShaderUniforms::surface_direction`)
+// ============================================================================
+
+/// Latitud en [-π/2, π/2] y longitud en (-π, π] de una dirección unitaria.
+/// El eje Y apunta al polo norte
+pub fn lat_lon(direction: Vector3) -> (f32, f32) {
+    let latitude = direction.y.clamp(-1.0, 1.0).asin();
+    let longitude = direction.z.atan2(direction.x);
+    (latitude, longitude)
+}
+
+/// Coordenadas equirectangulares (u, v) en [0, 1] calculadas desde la dirección:
+/// u recorre la longitud y v va del polo sur (0) al polo norte (1)
+pub fn spherical_uv(direction: Vector3) -> (f32, f32) {
+    let (latitude, longitude) = lat_lon(direction);
+    let u = longitude / std::f32::consts::TAU + 0.5;
+    let v = latitude / std::f32::consts::PI + 0.5;
+    (u, v)
+}
+
+/// Diferencia más corta entre dos coordenadas periódicas de período 1, en [-0.5, 0.5)
+pub fn wrap_delta(a: f32, b: f32) -> f32 {
+    (a - b + 0.5).rem_euclid(1.0) - 0.5
+}
+
+/// Punto sobre un cilindro que envuelve la longitud: cualquier ruido 3D evaluado
+/// aquí es periódico en u, sin costura. `periods` es la frecuencia a lo largo de u
+/// (distancia recorrida en una vuelta) y `v_scale` la frecuencia a lo largo de v
+pub fn longitude_cylinder(u: f32, v: f32, periods: f32, v_scale: f32) -> Vector3 {
+    let angle = u * std::f32::consts::TAU;
+    let radius = periods / std::f32::consts::TAU;
+    Vector3::new(angle.cos() * radius, angle.sin() * radius, v * v_scale)
+}
+
+/// Pesos de mezcla triplanar (suman 1) según la orientación de la superficie;
+/// `sharpness` mayor reduce la zona donde se mezclan dos proyecciones
+pub fn triplanar_weights(direction: Vector3, sharpness: f32) -> Vector3 {
+    let wx = direction.x.abs().powf(sharpness);
+    let wy = direction.y.abs().powf(sharpness);
+    let wz = direction.z.abs().powf(sharpness);
+    let total = (wx + wy + wz).max(1e-6);
+    Vector3::new(wx / total, wy / total, wz / total)
+}
+
+/// Mezcla triplanar: evalúa una función 2D sobre las proyecciones YZ, XZ y XY
+/// de la dirección escalada y las pondera con `triplanar_weights`
+pub fn triplanar(direction: Vector3, scale: f32, sharpness: f32, sample: impl Fn(f32, f32) -> f32) -> f32 {
+    let weights = triplanar_weights(direction, sharpness);
+    let p = direction * scale;
+    sample(p.y, p.z) * weights.x + sample(p.x, p.z) * weights.y + sample(p.x, p.y) * weights.z
+}
+
+// ============================================================================
+// LUZ ESTELAR
+// ============================================================================
+
+/// Color aproximado de un cuerpo negro a la temperatura indicada (Kelvin),
+/// según el ajuste de Tanner Helland. Válido entre 1000 K y 40000 K
+pub fn blackbody_color(kelvin: f32) -> ShaderColor {
+    let t = kelvin.clamp(1000.0, 40000.0) / 100.0;
+
+    let red = if t <= 66.0 {
+        255.0
+    } else {
+        329.69873 * (t - 60.0).powf(-0.13320476)
+    };
+    let green = if t <= 66.0 {
+        99.4708 * t.ln() - 161.11957
+    } else {
+        288.12216 * (t - 60.0).powf(-0.075514846)
+    };
+    let blue = if t >= 66.0 {
+        255.0
+    } else if t <= 19.0 {
+        0.0
+    } else {
+        138.51773 * (t - 10.0).ln() - 305.0448
+    };
+
+    ShaderColor::new(
+        red.clamp(0.0, 255.0) / 255.0,
+        green.clamp(0.0, 255.0) / 255.0,
+        blue.clamp(0.0, 255.0) / 255.0,
+        1.0,
+    )
+}
+
+/// Oscurecimiento del limbo con la ley cuadrática I(μ) = 1 - a(1 - μ) - b(1 - μ)²,
+/// donde μ es el coseno entre la normal y la dirección a la cámara
+pub fn limb_darkening(mu: f32, a: f32, b: f32) -> f32 {
+    let one_minus_mu = 1.0 - mu.clamp(0.0, 1.0);
+    (1.0 - a * one_minus_mu - b * one_minus_mu * one_minus_mu).max(0.0)
+}
+
+/// Peso del lado nocturno en [0, 1]: 0 a plena luz y 1 de noche. Sube con una
+/// banda suave alrededor del terminador (`normal · luz` ≈ 0), no con un corte
+pub fn night_mask(normal: Vector3, light_direction: Vector3) -> f32 {
+    smoothstep(0.1, -0.15, normal.dot(&light_direction))
+}
+
+// ============================================================================
+// NORMALES DESDE EL DESPLAZAMIENTO
+// Si un shader desplaza la superficie a lo largo de la normal con una función
+// escalar h(p), la normal correcta sale de derivar h en el plano tangente
+// ============================================================================
+
+/// Base tangente ortonormal (tangente, bitangente) con tangente × bitangente = normal
+pub fn tangent_frame(normal: Vector3) -> (Vector3, Vector3) {
+    let helper = if normal.y.abs() < 0.99 {
+        Vector3::new(0.0, 1.0, 0.0)
+    } else {
+        Vector3::new(1.0, 0.0, 0.0)
+    };
+    let tangent = helper.cross(&normal).normalize();
+    let bitangent = normal.cross(&tangent);
+    (tangent, bitangent)
+}
+
+/// Normal de la superficie desplazada p + n·h(p), calculada con diferencias
+/// centrales de `displacement` a lo largo de la base tangente. Funciona en
+/// cualquier espacio siempre que `position`, `normal` y la función coincidan:
+/// en el vertex shader da la normal del relieve y en el fragment shader sirve
+/// como bump mapping por píxel. `epsilon` es el paso de muestreo
+pub fn displaced_normal(position: Vector3, normal: Vector3, epsilon: f32, displacement: impl Fn(Vector3) -> f32) -> Vector3 {
+    let normal = normal.normalize();
+    let (tangent, bitangent) = tangent_frame(normal);
+
+    let dh_tangent = displacement(position + tangent * epsilon) - displacement(position - tangent * epsilon);
+    let dh_bitangent = displacement(position + bitangent * epsilon) - displacement(position - bitangent * epsilon);
+
+    // Derivadas de la superficie desplazada en cada dirección tangente
+    let surface_tangent = tangent * (2.0 * epsilon) + normal * dh_tangent;
+    let surface_bitangent = bitangent * (2.0 * epsilon) + normal * dh_bitangent;
+
+    surface_tangent.cross(&surface_bitangent).normalize()
+}
+
+// ============================================================================
+// RAMPAS DE COLOR (GRADIENTES)
+// Reemplazan las cadenas de if/else que convierten un valor de ruido en color
+// ============================================================================
+
+/// Forma de interpolar entre dos paradas consecutivas
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RampInterpolation {
+    /// Escalón: mantiene el color de la parada anterior
+    Constant,
+    Linear,
+    Smoothstep,
+    /// Catmull-Rom usando las paradas vecinas
+    Cubic,
+}
+
+/// Espacio de color en el que se mezclan las paradas
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RampColorSpace {
+    /// Mezcla directa de los valores sRGB (igual que `mix_color`)
+    Srgb,
+    LinearRgb,
+    Oklab,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ColorStop {
+    pub position: f32,
+    pub color: ShaderColor,
+}
+
+/// Gradiente con paradas arbitrarias. Dos paradas en la misma posición
+/// producen un corte duro entre colores.
+#[derive(Debug, Clone)]
+pub struct ColorRamp {
+    stops: Vec<ColorStop>,
+    pub interpolation: RampInterpolation,
+    pub color_space: RampColorSpace,
+}
+
+impl ColorRamp {
+    pub fn new(interpolation: RampInterpolation, color_space: RampColorSpace) -> Self {
+        ColorRamp {
+            stops: Vec::new(),
+            interpolation,
+            color_space,
+        }
+    }
+
+    /// Crea una rampa con los colores repartidos uniformemente en [0, 1]
+    pub fn from_colors(colors: &[ShaderColor], interpolation: RampInterpolation, color_space: RampColorSpace) -> Self {
+        let mut ramp = ColorRamp::new(interpolation, color_space);
+        let last = colors.len().saturating_sub(1).max(1) as f32;
+        for (i, color) in colors.iter().enumerate() {
+            ramp.add_stop(i as f32 / last, *color);
+        }
+        ramp
+    }
+
+    pub fn with_stop(mut self, position: f32, color: ShaderColor) -> Self {
+        self.add_stop(position, color);
+        self
+    }
+
+    /// Inserta una parada manteniendo el orden; con posiciones iguales
+    /// la nueva parada queda después de las existentes
+    pub fn add_stop(&mut self, position: f32, color: ShaderColor) {
+        let index = self.stops.partition_point(|stop| stop.position <= position);
+        self.stops.insert(index, ColorStop { position, color });
+    }
+
+    /// Evalúa la rampa en `t` (fuera del rango se usa la parada extrema)
+    pub fn sample(&self, t: f32) -> ShaderColor {
+        let (first, last) = match (self.stops.first(), self.stops.last()) {
+            (Some(first), Some(last)) => (first, last),
+            _ => return ShaderColor::BLACK,
+        };
+
+        if t.is_nan() || t <= first.position {
+            return first.color;
+        }
+        if t >= last.position {
+            return last.color;
+        }
+
+        // Última parada con posición <= t; la siguiente está estrictamente después
+        let index = self.stops.partition_point(|stop| stop.position <= t) - 1;
+        let start = self.stops[index];
+        let end = self.stops[index + 1];
+        let local = (t - start.position) / (end.position - start.position);
+
+        let weight = match self.interpolation {
+            RampInterpolation::Constant => return start.color,
+            RampInterpolation::Linear => local,
+            RampInterpolation::Smoothstep => local * local * (3.0 - 2.0 * local),
+            RampInterpolation::Cubic => {
+                let before = self.stops[index.saturating_sub(1)].color;
+                let after = self.stops[(index + 2).min(self.stops.len() - 1)].color;
+                return self.catmull_rom(before, start.color, end.color, after, local);
+            }
+        };
+
+        let a = to_ramp_space(start.color, self.color_space);
+        let b = to_ramp_space(end.color, self.color_space);
+        from_ramp_space(mix_color(a, b, weight), self.color_space)
+    }
+
+    fn catmull_rom(&self, c0: ShaderColor, c1: ShaderColor, c2: ShaderColor, c3: ShaderColor, t: f32) -> ShaderColor {
+        let p0 = to_ramp_space(c0, self.color_space);
+        let p1 = to_ramp_space(c1, self.color_space);
+        let p2 = to_ramp_space(c2, self.color_space);
+        let p3 = to_ramp_space(c3, self.color_space);
+
+        let spline = |a: f32, b: f32, c: f32, d: f32| -> f32 {
+            let t2 = t * t;
+            let t3 = t2 * t;
+            0.5 * (2.0 * b + (c - a) * t + (2.0 * a - 5.0 * b + 4.0 * c - d) * t2 + (3.0 * b - a - 3.0 * c + d) * t3)
+        };
+
+        let color = from_ramp_space(
+            ShaderColor::new(
+                spline(p0.r, p1.r, p2.r, p3.r),
+                spline(p0.g, p1.g, p2.g, p3.g),
+                spline(p0.b, p1.b, p2.b, p3.b),
+                spline(p0.a, p1.a, p2.a, p3.a),
+            ),
+            self.color_space,
+        );
+
+        // El spline puede salirse del rango entre paradas muy distintas
+        ShaderColor::new(
+            color.r.clamp(0.0, 1.0),
+            color.g.clamp(0.0, 1.0),
+            color.b.clamp(0.0, 1.0),
+            color.a.clamp(0.0, 1.0),
+        )
+    }
+
+    /// Serializa la rampa en un formato de texto de una línea por parada:
+    ///
+    /// ```text
+    /// interpolation smoothstep
+    /// space oklab
+    /// stop 0.0000 #141419ff
+    /// ```
+    pub fn to_text(&self) -> String {
+        let mut text = format!(
+            "interpolation {}\nspace {}\n",
+            interpolation_name(self.interpolation),
+            color_space_name(self.color_space),
+        );
+        for stop in &self.stops {
+            let c = stop.color.to_raylib_color();
+            text.push_str(&format!(
+                "stop {:.4} #{:02x}{:02x}{:02x}{:02x}\n",
+                stop.position, c.r, c.g, c.b, c.a
+            ));
+        }
+        text
+    }
+
+    /// Lee una rampa escrita con `to_text`. Las líneas vacías y las que
+    /// empiezan con `#` se ignoran.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut ramp = ColorRamp::new(RampInterpolation::Linear, RampColorSpace::Srgb);
+
+        for (line_number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            match parts.as_slice() {
+                ["interpolation", name] => {
+                    ramp.interpolation = parse_interpolation(name)
+                        .ok_or_else(|| format!("Línea {}: interpolación desconocida '{}'", line_number + 1, name))?;
+                },
+                ["space", name] => {
+                    ramp.color_space = parse_color_space(name)
+                        .ok_or_else(|| format!("Línea {}: espacio de color desconocido '{}'", line_number + 1, name))?;
+                },
+                ["stop", position, color] => {
+                    let position = position
+                        .parse::<f32>()
+                        .map_err(|e| format!("Línea {}: error parseando posición: {}", line_number + 1, e))?;
+                    let color = parse_hex_color(color)
+                        .ok_or_else(|| format!("Línea {}: color inválido '{}'", line_number + 1, color))?;
+                    ramp.add_stop(position, color);
+                },
+                _ => return Err(format!("Línea {}: no se reconoce '{}'", line_number + 1, line)),
+            }
+        }
+
+        if ramp.stops.is_empty() {
+            return Err("La rampa no tiene paradas".to_string());
+        }
+
+        Ok(ramp)
+    }
+
+    /// Carga una rampa desde una imagen de gradiente horizontal (tira PNG).
+    /// Se lee la fila central y cada columna se convierte en una parada.
+    pub fn from_gradient_png(path: &str, interpolation: RampInterpolation, color_space: RampColorSpace) -> Result<Self, String> {
+        let image = raylib::prelude::Image::load_image(path)
+            .map_err(|e| format!("No se pudo abrir la imagen de gradiente: {}", e))?;
+
+        let width = image.width().max(0) as usize;
+        let height = image.height().max(0) as usize;
+        if width == 0 || height == 0 {
+            return Err(format!("La imagen de gradiente '{}' está vacía", path));
+        }
+
+        let pixels = image.get_image_data();
+        let row = height / 2;
+        let last = (width - 1).max(1) as f32;
+
+        let mut ramp = ColorRamp::new(interpolation, color_space);
+        for x in 0..width {
+            let pixel = pixels[row * width + x];
+            let mut color = ShaderColor::from_rgb(pixel.r, pixel.g, pixel.b);
+            color.a = pixel.a as f32 / 255.0;
+            ramp.add_stop(x as f32 / last, color);
+        }
+
+        Ok(ramp)
+    }
+}
+
+fn interpolation_name(interpolation: RampInterpolation) -> &'static str {
+    match interpolation {
+        RampInterpolation::Constant => "constant",
+        RampInterpolation::Linear => "linear",
+        RampInterpolation::Smoothstep => "smoothstep",
+        RampInterpolation::Cubic => "cubic",
+    }
+}
+
+fn parse_interpolation(name: &str) -> Option<RampInterpolation> {
+    match name {
+        "constant" => Some(RampInterpolation::Constant),
+        "linear" => Some(RampInterpolation::Linear),
+        "smoothstep" => Some(RampInterpolation::Smoothstep),
+        "cubic" => Some(RampInterpolation::Cubic),
+        _ => None,
+    }
+}
+
+fn color_space_name(color_space: RampColorSpace) -> &'static str {
+    match color_space {
+        RampColorSpace::Srgb => "srgb",
+        RampColorSpace::LinearRgb => "linear_rgb",
+        RampColorSpace::Oklab => "oklab",
+    }
+}
+
+fn parse_color_space(name: &str) -> Option<RampColorSpace> {
+    match name {
+        "srgb" => Some(RampColorSpace::Srgb),
+        "linear_rgb" => Some(RampColorSpace::LinearRgb),
+        "oklab" => Some(RampColorSpace::Oklab),
+        _ => None,
+    }
+}
+
+// Acepta #rrggbb o #rrggbbaa
+fn parse_hex_color(text: &str) -> Option<ShaderColor> {
+    let hex = text.strip_prefix('#')?;
+    if !hex.is_ascii() || (hex.len() != 6 && hex.len() != 8) {
+        return None;
+    }
+
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    let mut color = ShaderColor::from_rgb(channel(0)?, channel(2)?, channel(4)?);
+    if hex.len() == 8 {
+        color.a = channel(6)? as f32 / 255.0;
+    }
+    Some(color)
+}
+
+fn srgb_to_linear(c: f32) -> f32 {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn linear_to_srgb(c: f32) -> f32 {
+    let c = c.max(0.0);
+    if c <= 0.0031308 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+// Convierte un color sRGB al espacio donde se interpola (Oklab se guarda en r=L, g=a, b=b)
+fn to_ramp_space(color: ShaderColor, color_space: RampColorSpace) -> ShaderColor {
+    match color_space {
+        RampColorSpace::Srgb => color,
+        RampColorSpace::LinearRgb => ShaderColor::new(
+            srgb_to_linear(color.r),
+            srgb_to_linear(color.g),
+            srgb_to_linear(color.b),
+            color.a,
+        ),
+        RampColorSpace::Oklab => {
+            let r = srgb_to_linear(color.r);
+            let g = srgb_to_linear(color.g);
+            let b = srgb_to_linear(color.b);
+
+            let l = (0.41222147 * r + 0.53633254 * g + 0.05144599 * b).cbrt();
+            let m = (0.2119035 * r + 0.6806995 * g + 0.10739696 * b).cbrt();
+            let s = (0.08830246 * r + 0.28171884 * g + 0.6299787 * b).cbrt();
+
+            ShaderColor::new(
+                0.21045426 * l + 0.7936178 * m - 0.00407205 * s,
+                1.9779985 * l - 2.4285922 * m + 0.4505937 * s,
+                0.02590404 * l + 0.78277177 * m - 0.80867577 * s,
+                color.a,
+            )
+        }
+    }
+}
+
+fn from_ramp_space(color: ShaderColor, color_space: RampColorSpace) -> ShaderColor {
+    match color_space {
+        RampColorSpace::Srgb => color,
+        RampColorSpace::LinearRgb => ShaderColor::new(
+            linear_to_srgb(color.r),
+            linear_to_srgb(color.g),
+            linear_to_srgb(color.b),
+            color.a,
+        ),
+        RampColorSpace::Oklab => {
+            let l = (color.r + 0.39633778 * color.g + 0.21580376 * color.b).powi(3);
+            let m = (color.r - 0.10556135 * color.g - 0.06385417 * color.b).powi(3);
+            let s = (color.r - 0.08948418 * color.g - 1.2914855 * color.b).powi(3);
+
+            ShaderColor::new(
+                linear_to_srgb(4.0767417 * l - 3.3077116 * m + 0.23096993 * s),
+                linear_to_srgb(-1.268438 * l + 2.6097574 * m - 0.3413194 * s),
+                linear_to_srgb(-0.00419609 * l - 0.7034186 * m + 1.7076147 * s),
+                color.a,
+            )
+        }
+    }
+}
+
+// ============================================================================
+// ÁRBOL DE NODOS DE RUIDO
+// Combinadores componibles (warp, fbm, ridged, terrazas, remapeo...) que
+// reemplazan las sumas manuales de `fbm` con pesos mágicos
+// ============================================================================
+
+/// Nodo de ruido evaluable en cualquier punto 3D
+pub trait NoiseNode {
+    fn eval(&self, p: Vector3) -> f32;
+
+    /// Evalúa el nodo sabiendo que un píxel cubre `footprint` unidades del
+    /// dominio en `p`. Los nodos fractales desvanecen las octavas por encima
+    /// del límite de Nyquist (que dejan de aportar) y los nodos que
+    /// transforman el dominio ajustan la huella; por defecto no se filtra
+    fn eval_filtered(&self, p: Vector3, _footprint: f32) -> f32 {
+        self.eval(p)
+    }
+}
+
+/// Ruido base de una fuente con semilla. Rango: aprox. [-1, 1]
+pub struct BasisNode {
+    pub noise: NoiseSource,
+    pub basis: NoiseBasis,
+}
+
+impl BasisNode {
+    pub fn new(seed: u32, basis: NoiseBasis) -> Self {
+        BasisNode {
+            noise: NoiseSource::new(seed),
+            basis,
+        }
+    }
+}
+
+impl NoiseNode for BasisNode {
+    fn eval(&self, p: Vector3) -> f32 {
+        self.noise.sample3(self.basis, p.x, p.y, p.z)
+    }
+}
+
+/// Escala y desplaza el dominio: evalúa la fuente en `p * frequency + offset`
+pub struct TransformNode {
+    pub source: Box<dyn NoiseNode>,
+    pub frequency: Vector3,
+    pub offset: Vector3,
+}
+
+impl NoiseNode for TransformNode {
+    fn eval(&self, p: Vector3) -> f32 {
+        let q = Vector3::new(p.x * self.frequency.x, p.y * self.frequency.y, p.z * self.frequency.z);
+        self.source.eval(q + self.offset)
+    }
+
+    fn eval_filtered(&self, p: Vector3, footprint: f32) -> f32 {
+        let q = Vector3::new(p.x * self.frequency.x, p.y * self.frequency.y, p.z * self.frequency.z);
+        // Con escala anisótropa se usa el eje más comprimido (el más propenso al aliasing)
+        let max_frequency = self.frequency.x.abs().max(self.frequency.y.abs()).max(self.frequency.z.abs());
+        self.source.eval_filtered(q + self.offset, footprint * max_frequency)
+    }
+}
+
+/// Movimiento browniano fraccional sobre cualquier nodo
+pub struct FbmNode {
+    pub source: Box<dyn NoiseNode>,
+    pub octaves: i32,
+    pub lacunarity: f32,
+    pub gain: f32,
+}
+
+impl NoiseNode for FbmNode {
+    fn eval(&self, p: Vector3) -> f32 {
+        self.eval_filtered(p, 0.0)
+    }
+
+    fn eval_filtered(&self, p: Vector3, footprint: f32) -> f32 {
+        let mut value = 0.0;
+        let mut amplitude = 0.5;
+        let mut frequency = 1.0;
+        
+        for _ in 0..self.octaves {
+            let weight = octave_fade(frequency, footprint);
+            if weight <= 0.0 {
+                break;
+            }
+            value += amplitude * weight * self.source.eval_filtered(p * frequency, footprint * frequency);
+            frequency *= self.lacunarity;
+            amplitude *= self.gain;
+        }
+        
+        value
+    }
+}
+
+/// Turbulencia: fbm del valor absoluto (pliegues marcados). Rango: [0, ~1)
+pub struct TurbulenceNode {
+    pub source: Box<dyn NoiseNode>,
+    pub octaves: i32,
+    pub lacunarity: f32,
+    pub gain: f32,
+}
+
+impl NoiseNode for TurbulenceNode {
+    fn eval(&self, p: Vector3) -> f32 {
+        self.eval_filtered(p, 0.0)
+    }
+
+    fn eval_filtered(&self, p: Vector3, footprint: f32) -> f32 {
+        let mut value = 0.0;
+        let mut amplitude = 0.5;
+        let mut frequency = 1.0;
+        
+        for _ in 0..self.octaves {
+            let weight = octave_fade(frequency, footprint);
+            if weight <= 0.0 {
+                break;
+            }
+            value += amplitude * weight * self.source.eval_filtered(p * frequency, footprint * frequency).abs();
+            frequency *= self.lacunarity;
+            amplitude *= self.gain;
+        }
+        
+        value
+    }
+}
+
+/// Billow: fbm de `2|n| - 1`, da formas redondeadas tipo nube. Rango: aprox. (-1, 1)
+pub struct BillowNode {
+    pub source: Box<dyn NoiseNode>,
+    pub octaves: i32,
+    pub lacunarity: f32,
+    pub gain: f32,
+}
+
+impl NoiseNode for BillowNode {
+    fn eval(&self, p: Vector3) -> f32 {
+        self.eval_filtered(p, 0.0)
+    }
+
+    fn eval_filtered(&self, p: Vector3, footprint: f32) -> f32 {
+        let mut value = 0.0;
+        let mut amplitude = 0.5;
+        let mut frequency = 1.0;
+        
+        for _ in 0..self.octaves {
+            let weight = octave_fade(frequency, footprint);
+            if weight <= 0.0 {
+                break;
+            }
+            value += amplitude * weight * (2.0 * self.source.eval_filtered(p * frequency, footprint * frequency).abs() - 1.0);
+            frequency *= self.lacunarity;
+            amplitude *= self.gain;
+        }
+        
+        value
+    }
+}
+
+/// Multifractal con crestas de Musgrave: cada octava se pondera con la
+/// anterior, así el detalle se concentra en las crestas. Rango: [0, ~1]
+pub struct RidgedMultiNode {
+    pub source: Box<dyn NoiseNode>,
+    pub octaves: i32,
+    pub lacunarity: f32,
+    pub gain: f32,
+    pub offset: f32,
+}
+
+impl NoiseNode for RidgedMultiNode {
+    fn eval(&self, p: Vector3) -> f32 {
+        self.eval_filtered(p, 0.0)
+    }
+
+    fn eval_filtered(&self, p: Vector3, footprint: f32) -> f32 {
+        let mut value = 0.0;
+        let mut weight = 1.0;
+        let mut amplitude = 1.0;
+        let mut frequency = 1.0;
+        let mut total_amplitude = 0.0;
+        
+        for _ in 0..self.octaves {
+            // Las octavas filtradas se descuentan también de la normalización
+            let fade = octave_fade(frequency, footprint);
+            if fade <= 0.0 {
+                break;
+            }
+            let source = self.source.eval_filtered(p * frequency, footprint * frequency);
+            let ridge = (self.offset - source.abs()).max(0.0);
+            let signal = ridge * ridge * weight;
+            weight = (signal * self.gain).clamp(0.0, 1.0);
+            
+            value += signal * amplitude * fade;
+            total_amplitude += amplitude * self.offset * self.offset * fade;
+            frequency *= self.lacunarity;
+            amplitude /= self.lacunarity;
+        }
+        
+        if total_amplitude > 0.0 {
+            value / total_amplitude
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Deformación del dominio: evalúa la fuente en `p + strength * warp(p)`.
+/// Con `iterations > 1` la deformación se aplica sobre sí misma
+/// (`f(p + w(p + w(p)))`), dando remolinos cada vez más enroscados.
+pub struct DomainWarpNode {
+    pub source: Box<dyn NoiseNode>,
+    pub warp: Box<dyn NoiseNode>,
+    pub strength: f32,
+    pub iterations: u32,
+}
+
+impl DomainWarpNode {
+    // Desplazamientos arbitrarios para obtener tres canales independientes del mismo nodo
+    const CHANNEL_OFFSETS: [Vector3; 3] = [
+        Vector3 { x: 0.0, y: 0.0, z: 0.0 },
+        Vector3 { x: 5.2, y: 1.3, z: 7.9 },
+        Vector3 { x: 1.7, y: 9.2, z: 3.4 },
+    ];
+
+    fn displacement(&self, p: Vector3) -> Vector3 {
+        Vector3::new(
+            self.warp.eval(p + Self::CHANNEL_OFFSETS[0]),
+            self.warp.eval(p + Self::CHANNEL_OFFSETS[1]),
+            self.warp.eval(p + Self::CHANNEL_OFFSETS[2]),
+        ) * self.strength
+    }
+}
+
+impl NoiseNode for DomainWarpNode {
+    fn eval(&self, p: Vector3) -> f32 {
+        let mut q = p;
+        for _ in 0..self.iterations.max(1) {
+            q = p + self.displacement(q);
+        }
+        self.source.eval(q)
+    }
+
+    fn eval_filtered(&self, p: Vector3, footprint: f32) -> f32 {
+        // La deformación es de baja frecuencia: solo se filtra la fuente
+        let mut q = p;
+        for _ in 0..self.iterations.max(1) {
+            q = p + self.displacement(q);
+        }
+        self.source.eval_filtered(q, footprint)
+    }
+}
+
+/// Terrazas: cuantiza el valor en `steps` escalones. `sharpness` = 0 da
+/// rampas suaves entre niveles y 1 da escalones duros
+pub struct TerraceNode {
+    pub source: Box<dyn NoiseNode>,
+    pub steps: f32,
+    pub sharpness: f32,
+}
+
+impl NoiseNode for TerraceNode {
+    fn eval(&self, p: Vector3) -> f32 {
+        self.eval_filtered(p, 0.0)
+    }
+
+    fn eval_filtered(&self, p: Vector3, footprint: f32) -> f32 {
+        let scaled = self.source.eval_filtered(p, footprint) * self.steps;
+        let level = scaled.floor();
+        let half_width = 0.5 * (1.0 - self.sharpness.clamp(0.0, 0.99));
+        let rise = smoothstep(0.5 - half_width, 0.5 + half_width, scaled - level);
+        (level + rise) / self.steps
+    }
+}
+
+/// Operaciones puntuales sobre el valor de un nodo
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ValueOp {
+    /// Mapea linealmente [from_min, from_max] a [to_min, to_max]
+    Remap { from_min: f32, from_max: f32, to_min: f32, to_max: f32 },
+    Clamp { min: f32, max: f32 },
+    /// Bias de Perlin sobre [0, 1]: `bias` < 0.5 oscurece, > 0.5 aclara
+    Bias(f32),
+    /// Gain de Perlin sobre [0, 1]: > 0.5 aumenta el contraste
+    Gain(f32),
+}
+
+impl ValueOp {
+    pub fn apply(&self, value: f32) -> f32 {
+        match *self {
+            ValueOp::Remap { from_min, from_max, to_min, to_max } => {
+                // Un rango de origen vacío no tiene pendiente: todo va a `to_min`
+                let width = from_max - from_min;
+                if width.abs() < f32::EPSILON {
+                    return to_min;
+                }
+                to_min + (value - from_min) / width * (to_max - to_min)
+            },
+            ValueOp::Clamp { min, max } => value.clamp(min, max),
+            ValueOp::Bias(bias) => bias_curve(value, bias),
+            ValueOp::Gain(gain) => {
+                if value < 0.5 {
+                    bias_curve(2.0 * value, 1.0 - gain) * 0.5
+                } else {
+                    1.0 - bias_curve(2.0 - 2.0 * value, 1.0 - gain) * 0.5
+                }
+            },
+        }
+    }
+}
+
+fn bias_curve(value: f32, bias: f32) -> f32 {
+    let value = value.clamp(0.0, 1.0);
+    value.powf(bias.clamp(0.001, 0.999).ln() / 0.5f32.ln())
+}
+
+pub struct ValueOpNode {
+    pub source: Box<dyn NoiseNode>,
+    pub op: ValueOp,
+}
+
+impl NoiseNode for ValueOpNode {
+    fn eval(&self, p: Vector3) -> f32 {
+        self.op.apply(self.source.eval(p))
+    }
+
+    fn eval_filtered(&self, p: Vector3, footprint: f32) -> f32 {
+        self.op.apply(self.source.eval_filtered(p, footprint))
+    }
+}
+
+/// Forma de combinar dos nodos
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CombineOp {
+    Add,
+    Multiply,
+}
+
+pub struct CombineNode {
+    pub a: Box<dyn NoiseNode>,
+    pub b: Box<dyn NoiseNode>,
+    pub op: CombineOp,
+}
+
+impl NoiseNode for CombineNode {
+    fn eval(&self, p: Vector3) -> f32 {
+        self.eval_filtered(p, 0.0)
+    }
+
+    fn eval_filtered(&self, p: Vector3, footprint: f32) -> f32 {
+        let a = self.a.eval_filtered(p, footprint);
+        let b = self.b.eval_filtered(p, footprint);
+        match self.op {
+            CombineOp::Add => a + b,
+            CombineOp::Multiply => a * b,
+        }
+    }
+}
+
+/// Métodos encadenables para construir árboles de ruido como una sola expresión:
+///
+/// ```ignore
+/// BasisNode::new(seed, NoiseBasis::Simplex)
+///     .fbm(5, 2.0, 0.5)
+///     .warp(BasisNode::new(seed + 1, NoiseBasis::Simplex).fbm(3, 2.0, 0.5), 0.4, 2)
+///     .remap(-0.6, 0.6, 0.0, 1.0)
+/// ```
+pub trait NoiseNodeExt: NoiseNode + Sized + 'static {
+    fn boxed(self) -> Box<dyn NoiseNode> {
+        Box::new(self)
+    }
+
+    fn scale(self, frequency: Vector3) -> TransformNode {
+        TransformNode { source: self.boxed(), frequency, offset: Vector3::new(0.0, 0.0, 0.0) }
+    }
+
+    fn fbm(self, octaves: i32, lacunarity: f32, gain: f32) -> FbmNode {
+        FbmNode { source: self.boxed(), octaves, lacunarity, gain }
+    }
+
+    fn turbulence(self, octaves: i32, lacunarity: f32, gain: f32) -> TurbulenceNode {
+        TurbulenceNode { source: self.boxed(), octaves, lacunarity, gain }
+    }
+
+    fn billow(self, octaves: i32, lacunarity: f32, gain: f32) -> BillowNode {
+        BillowNode { source: self.boxed(), octaves, lacunarity, gain }
+    }
+
+    fn ridged(self, octaves: i32, lacunarity: f32, gain: f32, offset: f32) -> RidgedMultiNode {
+        RidgedMultiNode { source: self.boxed(), octaves, lacunarity, gain, offset }
+    }
+
+    fn warp(self, warp: impl NoiseNode + 'static, strength: f32, iterations: u32) -> DomainWarpNode {
+        DomainWarpNode { source: self.boxed(), warp: Box::new(warp), strength, iterations }
+    }
+
+    fn terrace(self, steps: f32, sharpness: f32) -> TerraceNode {
+        TerraceNode { source: self.boxed(), steps, sharpness }
+    }
+
+    fn map(self, op: ValueOp) -> ValueOpNode {
+        ValueOpNode { source: self.boxed(), op }
+    }
+
+    fn remap(self, from_min: f32, from_max: f32, to_min: f32, to_max: f32) -> ValueOpNode {
+        self.map(ValueOp::Remap { from_min, from_max, to_min, to_max })
+    }
+
+    fn clamp(self, min: f32, max: f32) -> ValueOpNode {
+        self.map(ValueOp::Clamp { min, max })
+    }
+
+    fn bias(self, bias: f32) -> ValueOpNode {
+        self.map(ValueOp::Bias(bias))
+    }
+
+    fn gain(self, gain: f32) -> ValueOpNode {
+        self.map(ValueOp::Gain(gain))
+    }
+
+    fn combine(self, other: impl NoiseNode + 'static, op: CombineOp) -> CombineNode {
+        CombineNode { a: self.boxed(), b: Box::new(other), op }
+    }
+}
+
+impl<T: NoiseNode + 'static> NoiseNodeExt for T {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_color_close(a: ShaderColor, b: ShaderColor, tolerance: f32) {
+        let close = (a.r - b.r).abs() <= tolerance
+            && (a.g - b.g).abs() <= tolerance
+            && (a.b - b.b).abs() <= tolerance
+            && (a.a - b.a).abs() <= tolerance;
+        assert!(close, "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn ramp_text_round_trip_keeps_every_setting() {
+        let mut translucent = ShaderColor::from_rgb(30, 110, 170);
+        translucent.a = 128.0 / 255.0;
+        let ramp = ColorRamp::new(RampInterpolation::Cubic, RampColorSpace::Oklab)
+            .with_stop(0.0, ShaderColor::from_rgb(20, 20, 25))
+            .with_stop(0.125, translucent)
+            .with_stop(0.5, ShaderColor::from_rgb(120, 80, 60))
+            .with_stop(0.5, ShaderColor::from_rgb(80, 90, 100))
+            .with_stop(1.0, ShaderColor::from_rgb(255, 250, 230));
+
+        let parsed = ColorRamp::parse(&ramp.to_text()).unwrap();
+        assert_eq!(parsed.interpolation, RampInterpolation::Cubic);
+        assert_eq!(parsed.color_space, RampColorSpace::Oklab);
+        assert_eq!(parsed.stops.len(), ramp.stops.len());
+        for (a, b) in ramp.stops.iter().zip(&parsed.stops) {
+            assert!((a.position - b.position).abs() < 1e-4);
+            assert_color_close(a.color, b.color, 1e-6);
+        }
+    }
+
+    #[test]
+    fn ramp_text_round_trip_covers_all_modes() {
+        let interpolations = [RampInterpolation::Constant, RampInterpolation::Linear, RampInterpolation::Smoothstep, RampInterpolation::Cubic];
+        let spaces = [RampColorSpace::Srgb, RampColorSpace::LinearRgb, RampColorSpace::Oklab];
+        for interpolation in interpolations {
+            for color_space in spaces {
+                let ramp = ColorRamp::from_colors(&[ShaderColor::BLACK, ShaderColor::WHITE], interpolation, color_space);
+                let parsed = ColorRamp::parse(&ramp.to_text()).unwrap();
+                assert_eq!(parsed.interpolation, interpolation);
+                assert_eq!(parsed.color_space, color_space);
+            }
+        }
+    }
+
+    #[test]
+    fn ramp_text_keeps_every_channel_value() {
+        for value in 0..=255u8 {
+            let ramp = ColorRamp::new(RampInterpolation::Linear, RampColorSpace::Srgb)
+                .with_stop(0.0, ShaderColor::from_rgb(value, value, value));
+            let parsed = ColorRamp::parse(&ramp.to_text()).unwrap();
+            assert_color_close(parsed.stops[0].color, ramp.stops[0].color, 1e-6);
+        }
+    }
+
+    #[test]
+    fn ramp_parse_skips_comments_and_reports_bad_lines() {
+        let text = "# paleta de prueba\n\ninterpolation linear\nstop 0.5 #ff000080\n";
+        let ramp = ColorRamp::parse(text).unwrap();
+        assert_eq!(ramp.stops.len(), 1);
+        assert_color_close(ramp.stops[0].color, ShaderColor::new(1.0, 0.0, 0.0, 128.0 / 255.0), 1e-6);
+
+        let error = ColorRamp::parse("interpolation linear\ninterpolation bezier\n").unwrap_err();
+        assert!(error.contains("Línea 2"), "{}", error);
+        assert!(ColorRamp::parse("stop 0.5 #ff00\n").is_err());
+        assert!(ColorRamp::parse("stop medio #ff0000\n").is_err());
+        assert!(ColorRamp::parse("space cmyk\nstop 0 #ff0000\n").is_err());
+        assert!(ColorRamp::parse("interpolation linear\n").is_err());
+    }
+
+    #[test]
+    fn gradient_png_reports_a_missing_file() {
+        let result = ColorRamp::from_gradient_png("no_existe/gradiente.png", RampInterpolation::Linear, RampColorSpace::Srgb);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn ramp_sampling_follows_the_interpolation_mode() {
+        let colors = [ShaderColor::BLACK, ShaderColor::WHITE];
+        let constant = ColorRamp::from_colors(&colors, RampInterpolation::Constant, RampColorSpace::Srgb);
+        let linear = ColorRamp::from_colors(&colors, RampInterpolation::Linear, RampColorSpace::Srgb);
+        let smooth = ColorRamp::from_colors(&colors, RampInterpolation::Smoothstep, RampColorSpace::Srgb);
+        let cubic = ColorRamp::from_colors(&colors, RampInterpolation::Cubic, RampColorSpace::Srgb);
+
+        assert_eq!(constant.sample(0.99).r, 0.0);
+        assert!((linear.sample(0.25).r - 0.25).abs() < 1e-6);
+        assert!((smooth.sample(0.25).r - 0.15625).abs() < 1e-6);
+        // Con dos paradas el spline no tiene vecinos distintos y pasa por el punto medio
+        assert!((cubic.sample(0.5).r - 0.5).abs() < 1e-6);
+
+        // Fuera del rango se usan las paradas extremas
+        assert_eq!(linear.sample(-1.0).r, 0.0);
+        assert_eq!(linear.sample(2.0).r, 1.0);
+        assert_eq!(linear.sample(f32::NAN).r, 0.0);
+    }
+
+    #[test]
+    fn repeated_stop_makes_a_hard_edge() {
+        let red = ShaderColor::new(1.0, 0.0, 0.0, 1.0);
+        let blue = ShaderColor::new(0.0, 0.0, 1.0, 1.0);
+        let ramp = ColorRamp::new(RampInterpolation::Linear, RampColorSpace::Srgb)
+            .with_stop(0.0, red)
+            .with_stop(0.5, red)
+            .with_stop(0.5, blue)
+            .with_stop(1.0, blue);
+        assert_color_close(ramp.sample(0.499), red, 1e-6);
+        assert_color_close(ramp.sample(0.5), blue, 1e-6);
+    }
+
+    #[test]
+    fn ramp_color_spaces_round_trip() {
+        let color = ShaderColor::new(0.8, 0.3, 0.1, 0.5);
+        for color_space in [RampColorSpace::Srgb, RampColorSpace::LinearRgb, RampColorSpace::Oklab] {
+            let back = from_ramp_space(to_ramp_space(color, color_space), color_space);
+            assert_color_close(back, color, 1e-4);
+        }
+    }
+
+    #[test]
+    fn linear_rgb_midpoint_is_brighter_than_srgb() {
+        let colors = [ShaderColor::BLACK, ShaderColor::WHITE];
+        let srgb = ColorRamp::from_colors(&colors, RampInterpolation::Linear, RampColorSpace::Srgb);
+        let linear = ColorRamp::from_colors(&colors, RampInterpolation::Linear, RampColorSpace::LinearRgb);
+        // El punto medio en luz lineal es 0.5, que en sRGB vale ~0.735
+        assert!((linear.sample(0.5).r - 0.7354).abs() < 1e-3);
+        assert!(linear.sample(0.5).r > srgb.sample(0.5).r);
+    }
+
+    /// Nodo de valor fijo para probar los nodos que transforman valores
+    struct Fixed(f32);
+
+    impl NoiseNode for Fixed {
+        fn eval(&self, _p: Vector3) -> f32 {
+            self.0
+        }
+    }
+
+    const ORIGIN: Vector3 = Vector3 { x: 0.0, y: 0.0, z: 0.0 };
+
+    fn sample_points() -> impl Iterator<Item = Vector3> {
+        (0..500).map(|i| {
+            let t = i as f32 * 0.37;
+            Vector3::new(t.sin() * 3.1, (t * 1.7).cos() * 2.3, t * 0.11)
+        })
+    }
+
+    #[test]
+    fn terrace_holds_flat_levels() {
+        assert_eq!(Fixed(0.1).terrace(4.0, 1.0).eval(ORIGIN), 0.0);
+        assert_eq!(Fixed(0.2).terrace(4.0, 1.0).eval(ORIGIN), 0.25);
+        assert_eq!(Fixed(0.3).terrace(4.0, 1.0).eval(ORIGIN), 0.25);
+    }
+
+    #[test]
+    fn fractal_nodes_stay_in_range() {
+        let ridged = BasisNode::new(3, NoiseBasis::Value).ridged(4, 2.0, 2.0, 1.0);
+        let turbulence = BasisNode::new(3, NoiseBasis::Value).turbulence(3, 2.0, 0.5);
+        let billow = BasisNode::new(3, NoiseBasis::Simplex).billow(3, 2.0, 0.5);
+        for p in sample_points() {
+            assert!((0.0..=1.0 + 1e-4).contains(&ridged.eval(p)));
+            assert!((0.0..1.0).contains(&turbulence.eval(p)));
+            assert!((-1.0..1.0).contains(&billow.eval(p)));
+        }
+    }
+
+    #[test]
+    fn combine_adds_and_multiplies() {
+        assert_eq!(Fixed(0.25).combine(Fixed(0.5), CombineOp::Add).eval(ORIGIN), 0.75);
+        assert_eq!(Fixed(0.25).combine(Fixed(0.5), CombineOp::Multiply).eval(ORIGIN), 0.125);
+    }
+
+    #[test]
+    fn bias_and_gain_keep_endpoints() {
+        for op in [ValueOp::Bias(0.3), ValueOp::Gain(0.7)] {
+            assert!(op.apply(0.0).abs() < 1e-6);
+            assert!((op.apply(1.0) - 1.0).abs() < 1e-6);
+        }
+        assert!((ValueOp::Bias(0.3).apply(0.5) - 0.3).abs() < 1e-5);
+        assert!((ValueOp::Gain(0.7).apply(0.5) - 0.5).abs() < 1e-5);
+        // Gain > 0.5 aleja los valores del centro
+        assert!(ValueOp::Gain(0.7).apply(0.25) < 0.25);
+    }
+
+    #[test]
+    fn remap_with_empty_source_range_is_finite() {
+        let op = ValueOp::Remap { from_min: 0.5, from_max: 0.5, to_min: 0.2, to_max: 1.0 };
+        assert_eq!(op.apply(0.5), 0.2);
+        assert_eq!(op.apply(3.0), 0.2);
+        let op = ValueOp::Remap { from_min: -1.0, from_max: 1.0, to_min: 0.0, to_max: 1.0 };
+        assert_eq!(op.apply(0.0), 0.5);
+    }
+}
@@ -0,0 +1,100 @@
+// ============================================================================
+// SISTEMA SOLAR DE LA VISTA DE SISTEMA
+// Qué cuerpo orbita a cuál, con qué masa y con qué elementos orbitales.
+// Las masas están en unidades con G = 1; los ángulos que no fija la tabla
+// (nodo, periapsis y anomalía inicial) salen de una semilla fija
+// ============================================================================
+
+use crate::orbit::OrbitalElements;
+use crate::planets::PlanetRegistry;
+use crate::shaders::seeded_random;
+use std::f32::consts::TAU;
+
+/// Semilla de los ángulos libres de las órbitas
+const SYSTEM_SEED: u32 = 2024;
+
+/// Masa y órbita de los cuerpos del registro que no aparecen en la tabla
+const DEFAULT_MASS: f32 = 0.05;
+const EXTRA_ORBIT_SPACING: f32 = 4.0;
+
+/// Entrada de la tabla: el cuerpo `id` orbita a `parent` (ninguno = centro)
+struct SystemBody {
+    id: &'static str,
+    parent: Option<&'static str>,
+    mass: f32,
+    semi_major_axis: f32,
+    eccentricity: f32,
+    /// Inclinación en grados
+    inclination: f32,
+}
+
+const SYSTEM: [SystemBody; 9] = [
+    SystemBody { id: "star", parent: None, mass: 50.0, semi_major_axis: 0.0, eccentricity: 0.0, inclination: 0.0 },
+    SystemBody { id: "lava", parent: Some("star"), mass: 0.05, semi_major_axis: 4.5, eccentricity: 0.04, inclination: 1.5 },
+    SystemBody { id: "rocky", parent: Some("star"), mass: 0.03, semi_major_axis: 6.5, eccentricity: 0.2, inclination: 7.0 },
+    SystemBody { id: "terrestrial", parent: Some("star"), mass: 0.1, semi_major_axis: 9.0, eccentricity: 0.02, inclination: 0.0 },
+    SystemBody { id: "sci_fi", parent: Some("star"), mass: 0.08, semi_major_axis: 12.0, eccentricity: 0.08, inclination: 3.0 },
+    SystemBody { id: "metallic", parent: Some("star"), mass: 0.06, semi_major_axis: 15.0, eccentricity: 0.12, inclination: 2.0 },
+    SystemBody { id: "gas_giant", parent: Some("star"), mass: 2.8, semi_major_axis: 20.0, eccentricity: 0.05, inclination: 1.3 },
+    // Luna del gigante gaseoso, por fuera de sus anillos
+    SystemBody { id: "asteroid", parent: Some("gas_giant"), mass: 0.001, semi_major_axis: 2.6, eccentricity: 0.1, inclination: 8.0 },
+    SystemBody { id: "nebula", parent: Some("star"), mass: 0.4, semi_major_axis: 27.0, eccentricity: 0.03, inclination: 2.5 },
+];
+
+/// Lugar de un cuerpo en el sistema, por posición en el registro
+pub struct BodyPlacement {
+    /// Masa en unidades con G = 1
+    pub mass: f32,
+    /// Cuerpo central y órbita alrededor de él; `None` para el centro del sistema
+    pub orbit: Option<(usize, OrbitalElements)>,
+}
+
+/// Construye el sistema para los cuerpos del registro. Los que no están en la
+/// tabla orbitan al centro por fuera del último cuerpo
+pub fn layout(registry: &PlanetRegistry) -> Vec<BodyPlacement> {
+    let root = SYSTEM.iter()
+        .find(|body| body.parent.is_none())
+        .and_then(|body| registry.index_of(body.id));
+    let mass_of = |index: usize| {
+        registry.get_index(index)
+            .and_then(|descriptor| SYSTEM.iter().find(|body| body.id == descriptor.id))
+            .map_or(DEFAULT_MASS, |body| body.mass)
+    };
+    let mut next_extra_orbit = SYSTEM.iter().map(|body| body.semi_major_axis).fold(0.0, f32::max);
+
+    registry.iter()
+        .enumerate()
+        .map(|(index, descriptor)| {
+            let mass = mass_of(index);
+            let entry = SYSTEM.iter().find(|body| body.id == descriptor.id);
+
+            // Cuerpo central y forma de la órbita
+            let shape = match entry {
+                Some(body) => body.parent
+                    .and_then(|parent| registry.index_of(parent))
+                    .map(|parent| (parent, body.semi_major_axis, body.eccentricity, body.inclination)),
+                None => root.filter(|&root| root != index).map(|root| {
+                    next_extra_orbit += EXTRA_ORBIT_SPACING;
+                    (root, next_extra_orbit, 0.0, 0.0)
+                }),
+            };
+
+            let orbit = shape.map(|(parent, semi_major_axis, eccentricity, inclination)| {
+                let base = index as u32 * 3;
+                let elements = OrbitalElements::new(
+                    semi_major_axis,
+                    eccentricity,
+                    inclination.to_radians(),
+                    seeded_random(SYSTEM_SEED, base) * TAU,
+                    seeded_random(SYSTEM_SEED, base + 1) * TAU,
+                    seeded_random(SYSTEM_SEED, base + 2) * TAU,
+                    // Con G = 1, G·M es la masa del central (más la propia, en el problema de dos cuerpos)
+                    mass_of(parent) + mass,
+                );
+                (parent, elements)
+            });
+
+            BodyPlacement { mass, orbit }
+        })
+        .collect()
+}
@@ -3,6 +3,9 @@ use std::fs::File;
 use std::io::{BufRead, BufReader};
 use std::collections::HashMap;
 
+/// Radio de la esfera de `sphere.obj`; anillos y atmósferas se miden en múltiplos de él
+pub const PLANET_RADIUS: f32 = 0.5;
+
 #[derive(Debug, Clone)]
 pub struct Vertex {
     pub position: Vector3,
@@ -158,4 +161,148 @@ impl Mesh {
             indices,
         })
     }
-}
\ No newline at end of file
+
+    /// Anillo plano en el plano XZ entre `inner_radius` y `outer_radius`.
+    /// Tiene dos caras (una por lado, con normales opuestas) para que el descarte
+    /// por orden de vértices muestre siempre la que mira a la cámara.
+    /// UV: u = posición radial en [0, 1], v = ángulo en [0, 1]
+    pub fn annulus(inner_radius: f32, outer_radius: f32, segments: usize, radial_steps: usize) -> Mesh {
+        let mut vertices = Vec::new();
+        let mut indices = Vec::new();
+        
+        for (side, normal) in [Vector3::new(0.0, 1.0, 0.0), Vector3::new(0.0, -1.0, 0.0)].into_iter().enumerate() {
+            let base = vertices.len() as u32;
+            
+            for step in 0..=radial_steps {
+                let u = step as f32 / radial_steps as f32;
+                let radius = inner_radius + (outer_radius - inner_radius) * u;
+                
+                for segment in 0..=segments {
+                    let v = segment as f32 / segments as f32;
+                    let angle = v * std::f32::consts::TAU;
+                    vertices.push(Vertex {
+                        position: Vector3::new(angle.cos() * radius, 0.0, angle.sin() * radius),
+                        normal,
+                        uv: (u, v),
+                    });
+                }
+            }
+            
+            let row = (segments + 1) as u32;
+            for step in 0..radial_steps as u32 {
+                for segment in 0..segments as u32 {
+                    let a = base + step * row + segment;
+                    let b = a + 1;
+                    let c = a + row;
+                    let d = c + 1;
+                    
+                    // La cara inferior invierte el orden de los vértices
+                    if side == 0 {
+                        indices.extend_from_slice(&[a, b, c, b, d, c]);
+                    } else {
+                        indices.extend_from_slice(&[a, c, b, b, c, d]);
+                    }
+                }
+            }
+        }
+        
+        Mesh {
+            vertices,
+            indices,
+        }
+    }
+
+    /// Esfera de radio `PLANET_RADIUS` a partir de un icosaedro cuyos triángulos
+    /// se dividen en cuatro `subdivisions` veces. Los vértices se reparten de forma
+    /// casi uniforme (sin polos apretados) y se comparten entre caras, de modo que
+    /// la malla se puede deformar sin abrir grietas.
+    /// UV: u = longitud en [0, 1], v = latitud en [0, 1]
+    pub fn icosphere(subdivisions: u32) -> Mesh {
+        let t = (1.0 + 5.0_f32.sqrt()) / 2.0;
+        let mut directions: Vec<Vector3> = [
+            (-1.0, t, 0.0), (1.0, t, 0.0), (-1.0, -t, 0.0), (1.0, -t, 0.0),
+            (0.0, -1.0, t), (0.0, 1.0, t), (0.0, -1.0, -t), (0.0, 1.0, -t),
+            (t, 0.0, -1.0), (t, 0.0, 1.0), (-t, 0.0, -1.0), (-t, 0.0, 1.0),
+        ]
+        .iter()
+        .map(|&(x, y, z)| Vector3::new(x, y, z).normalize())
+        .collect();
+
+        // Caras en sentido antihorario vistas desde fuera
+        let mut faces: Vec<[u32; 3]> = vec![
+            [0, 11, 5], [0, 5, 1], [0, 1, 7], [0, 7, 10], [0, 10, 11],
+            [1, 5, 9], [5, 11, 4], [11, 10, 2], [10, 7, 6], [7, 1, 8],
+            [3, 9, 4], [3, 4, 2], [3, 2, 6], [3, 6, 8], [3, 8, 9],
+            [4, 9, 5], [2, 4, 11], [6, 2, 10], [8, 6, 7], [9, 8, 1],
+        ];
+
+        for _ in 0..subdivisions {
+            // Punto medio de cada arista, compartido por las dos caras que la usan
+            let mut midpoints: HashMap<(u32, u32), u32> = HashMap::new();
+            let mut midpoint = |a: u32, b: u32, directions: &mut Vec<Vector3>| -> u32 {
+                let key = (a.min(b), a.max(b));
+                *midpoints.entry(key).or_insert_with(|| {
+                    directions.push((directions[a as usize] + directions[b as usize]).normalize());
+                    directions.len() as u32 - 1
+                })
+            };
+
+            let mut next_faces = Vec::with_capacity(faces.len() * 4);
+            for [a, b, c] in faces {
+                let ab = midpoint(a, b, &mut directions);
+                let bc = midpoint(b, c, &mut directions);
+                let ca = midpoint(c, a, &mut directions);
+                next_faces.extend_from_slice(&[[a, ab, ca], [b, bc, ab], [c, ca, bc], [ab, bc, ca]]);
+            }
+            faces = next_faces;
+        }
+
+        let vertices = directions
+            .iter()
+            .map(|direction| Vertex {
+                position: *direction * PLANET_RADIUS,
+                normal: *direction,
+                uv: (
+                    0.5 + direction.z.atan2(direction.x) / std::f32::consts::TAU,
+                    0.5 + direction.y.clamp(-1.0, 1.0).asin() / std::f32::consts::PI,
+                ),
+            })
+            .collect();
+
+        Mesh {
+            vertices,
+            indices: faces.into_iter().flatten().collect(),
+        }
+    }
+
+    /// Recalcula normales suaves después de deformar los vértices: cada vértice
+    /// promedia las normales de las caras que lo usan, ponderadas por su área
+    pub fn recompute_normals(&mut self) {
+        let mut normals = vec![Vector3::new(0.0, 0.0, 0.0); self.vertices.len()];
+
+        for triangle in self.indices.chunks_exact(3) {
+            let [a, b, c] = [triangle[0] as usize, triangle[1] as usize, triangle[2] as usize];
+            let edge1 = self.vertices[b].position - self.vertices[a].position;
+            let edge2 = self.vertices[c].position - self.vertices[a].position;
+            // El producto cruz mide el doble del área: las caras grandes pesan más
+            let face_normal = edge1.cross(&edge2);
+            for index in [a, b, c] {
+                normals[index] = normals[index] + face_normal;
+            }
+        }
+
+        for (vertex, normal) in self.vertices.iter_mut().zip(normals) {
+            if normal.length() > 0.0 {
+                vertex.normal = normal.normalize();
+            }
+        }
+    }
+
+    /// Distancia máxima de un vértice al origen del modelo
+    pub fn bounding_radius(&self) -> f32 {
+        self.vertices
+            .iter()
+            .map(|vertex| vertex.position.length())
+            .fold(0.0, f32::max)
+    }
+}
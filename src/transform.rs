@@ -77,6 +77,55 @@ impl Transform4x4 {
             Vector3::new(transformed_x, transformed_y, transformed_z)
         }
     }
+
+    /// Aplica solo la parte lineal (sin traslación); útil para normales y direcciones
+    pub fn apply_to_direction(&self, vec: &Vector3) -> Vector3 {
+        Vector3::new(
+            self.elements[0][0] * vec.x + self.elements[0][1] * vec.y + self.elements[0][2] * vec.z,
+            self.elements[1][0] * vec.x + self.elements[1][1] * vec.y + self.elements[1][2] * vec.z,
+            self.elements[2][0] * vec.x + self.elements[2][1] * vec.y + self.elements[2][2] * vec.z,
+        )
+    }
+
+    /// Matriz inversa por eliminación de Gauss-Jordan con pivoteo parcial.
+    /// Si la matriz es singular devuelve la identidad
+    pub fn inverse(&self) -> Transform4x4 {
+        let mut source = self.elements;
+        let mut result = Transform4x4::identity().elements;
+
+        for col in 0..4 {
+            // Elegir la fila con el mayor pivote para estabilidad numérica
+            let mut pivot_row = col;
+            for row in (col + 1)..4 {
+                if source[row][col].abs() > source[pivot_row][col].abs() {
+                    pivot_row = row;
+                }
+            }
+            if source[pivot_row][col].abs() < 1e-8 {
+                return Transform4x4::identity();
+            }
+            source.swap(col, pivot_row);
+            result.swap(col, pivot_row);
+
+            let pivot = source[col][col];
+            for k in 0..4 {
+                source[col][k] /= pivot;
+                result[col][k] /= pivot;
+            }
+
+            for row in 0..4 {
+                if row != col {
+                    let factor = source[row][col];
+                    for k in 0..4 {
+                        source[row][k] -= factor * source[col][k];
+                        result[row][k] -= factor * result[col][k];
+                    }
+                }
+            }
+        }
+
+        Transform4x4 { elements: result }
+    }
 }
 
 /// Crea una matriz de vista (lookAt) para la cámara
@@ -173,6 +222,16 @@ pub fn build_y_axis_rotation(radians: f32) -> Transform4x4 {
     )
 }
 
+/// Crea un escalado uniforme
+pub fn build_uniform_scale(factor: f32) -> Transform4x4 {
+    Transform4x4::from_elements(
+        factor, 0.0, 0.0, 0.0,
+        0.0, factor, 0.0, 0.0,
+        0.0, 0.0, factor, 0.0,
+        0.0, 0.0, 0.0, 1.0,
+    )
+}
+
 /// Crea una transformación de traslación
 pub fn build_translation(offset_x: f32, offset_y: f32, offset_z: f32) -> Transform4x4 {
     Transform4x4::from_elements(
@@ -3,6 +3,8 @@
 // ============================================================================
 
 use raylib::prelude::*;
+use crate::planets::PlanetRegistry;
+use crate::shaders::ShaderParams;
 
 pub struct UIConfig {
     pub margin: i32,
@@ -24,60 +26,67 @@ impl Default for UIConfig {
     }
 }
 
-pub struct PlanetInfo {
-    pub name: &'static str,
-    pub key: &'static str,
-    pub description: &'static str,
-    pub icon: &'static str,
-    pub color: Color,
+/// Estado de la simulación que se muestra en el panel
+pub struct SimulationStatus {
+    /// Vista de sistema (todos los cuerpos) o vista individual
+    pub scene_mode: bool,
+    /// Instante del reloj de simulación, en segundos
+    pub time: f64,
+    /// Segundos de simulación por segundo real, sin signo
+    pub speed: f32,
+    pub paused: bool,
+    /// El tiempo corre hacia atrás
+    pub reversed: bool,
+    /// Instante que se está tecleando para saltar a él, si hay uno
+    pub jump_entry: Option<String>,
+    /// Estado de la gravedad de N cuerpos, si está activa
+    pub gravity: Option<GravityReadout>,
 }
 
-pub const PLANET_INFO: [PlanetInfo; 5] = [
-    PlanetInfo {
-        name: "Planeta Rocoso",
-        key: "1",
-        description: "Terreno deformado con cráteres",
-        icon: "🪨",
-        color: Color::new(160, 160, 160, 255),
-    },
-    PlanetInfo {
-        name: "Gigante Gaseoso",
-        key: "2",
-        description: "Bandas atmosféricas como Júpiter",
-        icon: "🌍",
-        color: Color::new(200, 120, 50, 255),
-    },
-    PlanetInfo {
-        name: "Planeta Sci-Fi",
-        key: "3",
-        description: "Circuitos y energía tecnológica",
-        icon: "⚡",
-        color: Color::new(0, 255, 255, 255),
-    },
-    PlanetInfo {
-        name: "Planeta Nebulosa",
-        key: "4",
-        description: "Gas cósmico etéreo",
-        icon: "🌌",
-        color: Color::new(200, 0, 150, 255),
-    },
-    PlanetInfo {
-        name: "Planeta Metálico",
-        key: "5",
-        description: "Superficie con picos metálicos",
-        icon: "⚙️",
-        color: Color::new(200, 210, 220, 255),
-    },
-];
+/// Integrador en uso y cuánto se han desviado las cantidades conservadas
+pub struct GravityReadout {
+    pub integrator: &'static str,
+    /// Desviación relativa de la energía total
+    pub energy_drift: f64,
+    /// Cambio del momento lineal total
+    pub momentum_drift: f64,
+    /// Segundos de simulación que la física va por detrás del reloj
+    pub lag: f64,
+}
+
+/// Cantidad de planetas por página (uno por cada tecla 1-9)
+pub const PLANETS_PER_PAGE: usize = 9;
 
-pub fn render_ui(d: &mut RaylibDrawHandle, current_planet: usize, fps: i32) {
+pub fn render_ui(
+    d: &mut RaylibDrawHandle,
+    registry: &PlanetRegistry,
+    current_planet: usize,
+    current_page: usize,
+    params: &ShaderParams,
+    status: &SimulationStatus,
+    fps: i32,
+) {
     let config = UIConfig::default();
     let width = d.get_screen_width();
     let height = d.get_screen_height();
     
-    // Panel semi-transparente de fondo
+    let page_count = if registry.is_empty() { 1 } else { registry.len().div_ceil(PLANETS_PER_PAGE) };
+    let page_start = current_page * PLANETS_PER_PAGE;
+    let page_entries = registry.len().saturating_sub(page_start).min(PLANETS_PER_PAGE);
+    // La descripción y los parámetros solo se dibujan si el planeta activo está en esta página
+    let current_on_page = (page_start..page_start + page_entries).contains(&current_planet);
+    let detail_rows = if current_on_page {
+        config.font_size_small + 8 + params.iter().count() as i32 * (config.font_size_small + 4)
+    } else {
+        0
+    };
+    
+    // Panel semi-transparente de fondo (la altura depende de la página)
     let panel_width = 380;
-    let panel_height = 280;
+    let panel_height = 304
+        + page_entries as i32 * (config.font_size_normal + 5)
+        + detail_rows
+        + if status.gravity.is_some() { config.font_size_small + 4 } else { 0 };
     let panel_x = config.margin;
     let panel_y = config.margin;
     
@@ -120,9 +129,9 @@ pub fn render_ui(d: &mut RaylibDrawHandle, current_planet: usize, fps: i32) {
     );
     y_offset += 10;
     
-    // Lista de planetas
-    for (i, info) in PLANET_INFO.iter().enumerate() {
-        let is_current = i == current_planet;
+    // Lista de planetas de la página actual
+    for (slot, info) in registry.iter().skip(page_start).take(PLANETS_PER_PAGE).enumerate() {
+        let is_current = page_start + slot == current_planet;
         let text_color = if is_current {
             Color::new(255, 255, 100, 255)  
         } else {
@@ -147,7 +156,7 @@ pub fn render_ui(d: &mut RaylibDrawHandle, current_planet: usize, fps: i32) {
         }
         
         d.draw_text(
-            &format!("[{}] {} {}", info.key, info.icon, info.name),
+            &format!("[{}] {} {}", slot + 1, info.icon, info.name),
             panel_x + config.panel_padding,
             y_offset,
             config.font_size_normal,
@@ -164,6 +173,18 @@ pub fn render_ui(d: &mut RaylibDrawHandle, current_planet: usize, fps: i32) {
                 Color::new(150, 150, 200, 255),
             );
             y_offset += config.font_size_small + 8;
+            
+            // Parámetros activos del shader
+            for (name, value) in params.iter() {
+                d.draw_text(
+                    &format!("      {} = {:.2}", name, value),
+                    panel_x + config.panel_padding + 10,
+                    y_offset,
+                    config.font_size_small,
+                    Color::new(130, 130, 170, 255),
+                );
+                y_offset += config.font_size_small + 4;
+            }
         }
     }
     
@@ -178,6 +199,16 @@ pub fn render_ui(d: &mut RaylibDrawHandle, current_planet: usize, fps: i32) {
     );
     y_offset += 10;
     
+    // Indicador de página
+    d.draw_text(
+        &format!("Página {}/{}  (RePág / AvPág)", current_page + 1, page_count),
+        panel_x + config.panel_padding,
+        y_offset,
+        config.font_size_small,
+        Color::new(150, 150, 200, 255),
+    );
+    y_offset += config.font_size_small + 8;
+    
     // Controles adicionales
     d.draw_text(
         "🖱️  Mouse: Rotar cámara y zoom",
@@ -186,6 +217,115 @@ pub fn render_ui(d: &mut RaylibDrawHandle, current_planet: usize, fps: i32) {
         config.font_size_small,
         Color::new(180, 180, 180, 255),
     );
+    y_offset += config.font_size_small + 4;
+    
+    d.draw_text(
+        "[R] Nueva semilla  [P] Exportar paletas",
+        panel_x + config.panel_padding,
+        y_offset,
+        config.font_size_small,
+        Color::new(180, 180, 180, 255),
+    );
+    y_offset += config.font_size_small + 4;
+    
+    d.draw_text(
+        "[X] Mostrar ejes de giro",
+        panel_x + config.panel_padding,
+        y_offset,
+        config.font_size_small,
+        Color::new(180, 180, 180, 255),
+    );
+    y_offset += config.font_size_small + 4;
+    
+    // En la vista de sistema las teclas 1-9 enfocan la cámara en vez de cambiar de planeta
+    let mode_text = if status.scene_mode {
+        "[TAB] Vista de sistema: 1-9 enfocan el cuerpo"
+    } else {
+        "[TAB] Vista individual: 1-9 cambian de planeta"
+    };
+    d.draw_text(
+        mode_text,
+        panel_x + config.panel_padding,
+        y_offset,
+        config.font_size_small,
+        Color::new(180, 180, 180, 255),
+    );
+    y_offset += config.font_size_small + 4;
+    
+    // Reloj de simulación: instante, velocidad y estado
+    let clock_state = if status.paused {
+        "  (en pausa)"
+    } else if status.reversed {
+        "  (hacia atrás)"
+    } else {
+        ""
+    };
+    d.draw_text(
+        &format!("Tiempo: {:.2} s  x{}{}", status.time, status.speed, clock_state),
+        panel_x + config.panel_padding,
+        y_offset,
+        config.font_size_small,
+        Color::new(100, 200, 255, 255),
+    );
+    y_offset += config.font_size_small + 4;
+    
+    d.draw_text(
+        "[ESPACIO] Pausa  [.] Paso  [B] Sentido",
+        panel_x + config.panel_padding,
+        y_offset,
+        config.font_size_small,
+        Color::new(180, 180, 180, 255),
+    );
+    y_offset += config.font_size_small + 4;
+    
+    d.draw_text(
+        if status.gravity.is_some() { "[+/-] Velocidad (x0.1 a x100 con gravedad)" } else { "[+/-] Velocidad (x0.1 a x1000)" },
+        panel_x + config.panel_padding,
+        y_offset,
+        config.font_size_small,
+        Color::new(180, 180, 180, 255),
+    );
+    y_offset += config.font_size_small + 4;
+    
+    // Mientras se teclea un salto, la línea muestra lo escrito
+    let jump_text = match &status.jump_entry {
+        Some(entry) => format!("Saltar a t = {}_  [ENTER]  [T] Cancelar", entry),
+        None => "[T] Saltar a un instante  [INICIO] t = 0".to_string(),
+    };
+    d.draw_text(
+        &jump_text,
+        panel_x + config.panel_padding,
+        y_offset,
+        config.font_size_small,
+        Color::new(180, 180, 180, 255),
+    );
+    y_offset += config.font_size_small + 4;
+    
+    // Órbitas de Kepler o gravedad de N cuerpos con su deriva
+    let gravity_text = match &status.gravity {
+        Some(gravity) => format!("[G] Gravedad: N cuerpos  [I] {}", gravity.integrator),
+        None => "[G] Gravedad: órbitas de Kepler".to_string(),
+    };
+    d.draw_text(
+        &gravity_text,
+        panel_x + config.panel_padding,
+        y_offset,
+        config.font_size_small,
+        Color::new(180, 180, 180, 255),
+    );
+    
+    if let Some(gravity) = &status.gravity {
+        y_offset += config.font_size_small + 4;
+        // El retraso solo se muestra cuando la física no da abasto
+        let lag_text = if gravity.lag > 0.05 { format!("  retraso {:.1} s", gravity.lag) } else { String::new() };
+        d.draw_text(
+            &format!("    Deriva: energía {:.1e}  momento {:.1e}{}", gravity.energy_drift, gravity.momentum_drift, lag_text),
+            panel_x + config.panel_padding,
+            y_offset,
+            config.font_size_small,
+            Color::new(150, 150, 200, 255),
+        );
+    }
     
     // FPS en la esquina superior derecha
     let fps_color = if fps >= 55 {
@@ -205,12 +345,14 @@ pub fn render_ui(d: &mut RaylibDrawHandle, current_planet: usize, fps: i32) {
         fps_color,
     );
     
-    let current_name = PLANET_INFO[current_planet].name;
-    d.draw_text(
-        current_name,
-        (width - 250) / 2,
-        height - 50,
-        24,
-        PLANET_INFO[current_planet].color,
-    );
+    if let Some(current) = registry.get_index(current_planet) {
+        let name_color = current.color.to_raylib_color();
+        d.draw_text(
+            current.name,
+            (width - 250) / 2,
+            height - 50,
+            24,
+            name_color,
+        );
+    }
 }